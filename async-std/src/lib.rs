@@ -6,32 +6,55 @@ use std::{
     ops::Deref,
     os::unix::{
         fs::{FileTypeExt, MetadataExt},
-        io::{AsRawFd, FromRawFd, RawFd},
+        io::{AsRawFd, FromRawFd, IntoRawFd, RawFd},
     },
     pin::Pin,
     sync::Arc,
-    task::{Context, Poll},
+    task::{ready, Context, Poll},
+    time::{Duration, Instant},
 };
 
-use gpiod_core::{invalid_input, major, minor, set_nonblock, Internal, Result};
+use futures_util::Stream;
+#[cfg(feature = "v2")]
+use gpiod_core::EventBuffer;
+use gpiod_core::{invalid_input, major, minor, select_chip_by_label, set_nonblock, Internal};
 
 pub use gpiod_core::{
-    Active, AsValues, AsValuesMut, Bias, BitId, ChipInfo, Direction, DirectionType, Drive, Edge,
-    EdgeDetect, Event, Input, LineId, LineInfo, Masked, Options, Output, Values, ValuesInfo,
-    MAX_BITS, MAX_VALUES,
+    abi_version, sort_chips, AbiVersion, Active, AsValues, AsValuesMut, Bias, BitId, BitOrder,
+    ChipInfo, Direction, DirectionType, Drive, Edge, EdgeDetect, Error, Event, EventClock,
+    EventRecord, EventStats, Input, LineAttribute, LineCapabilities, LineChange, LineChangeKind,
+    LineConfig, LineEventStats, LineId, LineInfo, Masked, MaskedIter, NameMatch, Options, Output,
+    RequestConfig, Result, TimeFormat, Values, ValuesInfo, WidthValues, MAX_BITS, MAX_CONSUMER_LEN,
+    MAX_VALUES,
 };
 
+#[cfg(feature = "embedded-hal-async")]
+mod hal;
+
+#[cfg(feature = "embedded-hal-async")]
+pub use hal::PinError;
+
+/// Get the version of this crate
+pub fn version() -> &'static str {
+    env!("CARGO_PKG_VERSION")
+}
+
 use async_io::Async;
 use async_std::{
     fs,
     fs::OpenOptions,
-    io::{Read, ReadExt},
+    future,
+    io::Read,
     os::unix::fs::OpenOptionsExt,
     path::{Path, PathBuf},
     stream::StreamExt,
+    task,
     task::spawn_blocking as asyncify,
 };
 
+#[cfg(feature = "v2")]
+use async_std::io::ReadExt;
+
 #[doc(hidden)]
 pub struct File {
     // use file to call close when drop
@@ -51,6 +74,50 @@ impl File {
         core::mem::forget(file);
         Self::from_fd(fd)
     }
+
+    pub fn try_clone(&self) -> Result<Self> {
+        let file = self.inner.get_ref().try_clone()?;
+        Self::from_fd(file.into_raw_fd())
+    }
+
+    pub fn into_std(self) -> Result<std::fs::File> {
+        Ok(self.inner.into_inner()?)
+    }
+
+    pub async fn readable(&self) -> Result<()> {
+        Ok(self.inner.readable().await?)
+    }
+
+    pub async fn read_shared(&self, buf: &mut [u8]) -> Result<usize> {
+        use std::io::Read;
+
+        self.read_with(|mut inner| Ok(inner.read(buf)?)).await
+    }
+
+    pub async fn read_with<R>(&self, mut op: impl FnMut(&std::fs::File) -> Result<R>) -> Result<R> {
+        loop {
+            match op(self.inner.get_ref()) {
+                Err(error) if error.kind() == std::io::ErrorKind::WouldBlock => {}
+                result => return result,
+            }
+            self.inner.readable().await?;
+        }
+    }
+
+    pub fn poll_read_with<R>(
+        &self,
+        cx: &mut Context<'_>,
+        mut op: impl FnMut(&std::fs::File) -> Result<R>,
+    ) -> Poll<Result<R>> {
+        loop {
+            ready!(self.inner.poll_readable(cx))?;
+
+            match op(self.inner.get_ref()) {
+                Err(error) if error.kind() == std::io::ErrorKind::WouldBlock => continue,
+                result => return Poll::Ready(result),
+            }
+        }
+    }
 }
 
 impl AsRawFd for File {
@@ -64,7 +131,7 @@ impl Read for File {
         self: Pin<&mut Self>,
         cx: &mut Context<'_>,
         buf: &mut [u8],
-    ) -> Poll<Result<usize>> {
+    ) -> Poll<std::io::Result<usize>> {
         use std::io::Read;
 
         match self.inner.poll_readable(cx) {
@@ -80,11 +147,18 @@ impl Read for File {
 ///
 /// Use [Chip::request_lines] with [Options::input] or [Options::output] to configure specific
 /// GPIO lines for input or output.
+///
+/// Lines are [Send] and [Sync]. Getting and setting values requires shared reference only,
+/// so lines can be shared between threads via [std::sync::Arc] without any locking.
+/// Reading events requires exclusive reference, so single consumer must own the lines
+/// (or wrap it into a mutex).
 pub struct Lines<Direction> {
     dir: PhantomData<Direction>,
     info: Arc<Internal<ValuesInfo>>,
     // wrap file to call close on drop
     file: File,
+    #[cfg(feature = "v2")]
+    events: EventBuffer,
 }
 
 impl<Direction> Deref for Lines<Direction> {
@@ -95,17 +169,142 @@ impl<Direction> Deref for Lines<Direction> {
     }
 }
 
+impl<Direction: DirectionType> fmt::Debug for Lines<Direction> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Lines")
+            .field("fd", &self.file.as_raw_fd())
+            .field("config", &self.request_config())
+            .finish()
+    }
+}
+
 impl<Direction: DirectionType> Lines<Direction> {
+    /// Get options which can be used to request the same lines again
+    ///
+    /// The options are reconstructed from the configuration of this request,
+    /// so passing it to [Chip::request_lines] re-acquires lines identically
+    /// (for example after the lines was released due to an error).
+    pub fn options(&self) -> Options<Direction, Vec<LineId>, String> {
+        self.info.options()
+    }
+
+    /// Get snapshot of configuration used to request lines
+    ///
+    /// Unlike [Lines::config] it does not query kernel, so it is cheap enough
+    /// to be used for logging and debugging.
+    pub fn request_config(&self) -> RequestConfig {
+        self.info.request_config::<Direction>()
+    }
+
+    /// Get actual configuration of requested lines
+    ///
+    /// The info of lines is re-read from chip, so it reflects flags which was really applied
+    /// by kernel (for example bias can be ignored for lines which does not support it).
+    /// The chip descriptor duplicated on request is used, so the chip is not re-opened.
+    pub async fn config(&self) -> Result<Vec<LineInfo>> {
+        let info = self.info.clone();
+
+        asyncify(move || info.lines_info()).await
+    }
+
+    /// Get kernel names of requested lines
+    ///
+    /// The names are in order of requested lines (unnamed lines has empty names).
+    /// The names of all chip lines are read once and cached by the chip.
+    pub async fn line_names(&self) -> Result<Vec<String>> {
+        let info = self.info.clone();
+
+        asyncify(move || info.line_names()).await
+    }
+
+    /// Change configuration of lines without releasing it
+    ///
+    /// Useful to toggle bias or edge detection at runtime without glitches on outputs.
+    /// The lines should be same as requested, while consumer string is ignored.
+    /// With ABI v1 edge detection and per-line options cannot be reconfigured.
+    ///
+    /// Unlike other operations it does not block, so it is not async. It fails when
+    /// another operation on lines is still in progress.
+    pub fn reconfigure(
+        &mut self,
+        options: Options<Direction, impl AsRef<[LineId]>, impl AsRef<str>>,
+    ) -> Result<()> {
+        let fd = self.file.as_raw_fd();
+
+        Arc::get_mut(&mut self.info)
+            .ok_or_else(|| {
+                Error::from(std::io::Error::new(
+                    std::io::ErrorKind::ResourceBusy,
+                    "Lines are busy",
+                ))
+            })?
+            .reconfigure(fd, &options)
+    }
+
+    /// Release lines explicitly
+    ///
+    /// Unlike dropping lines it reports failure of closing the request. After successful
+    /// release the lines can be requested again immediately (ex. with different options).
+    pub fn release(self) -> Result<()> {
+        let Self { info, file, .. } = self;
+
+        let result = gpiod_core::close_fd(file.into_std()?.into_raw_fd());
+
+        drop(info);
+
+        result
+    }
+
+    /// Wait until lines have something to read
+    ///
+    /// For inputs requested with edge detection this means that at least one event can be read
+    /// using [Lines::read_event] without blocking. Outputs and inputs without edge detection
+    /// have no event source, so waiting on them simply times out.
+    ///
+    /// Returns `false` when timeout elapsed. The `None` timeout means waiting infinitely.
+    pub async fn wait_readable(&self, timeout: Option<Duration>) -> Result<bool> {
+        if let Some(timeout) = timeout {
+            match future::timeout(timeout, self.file.readable()).await {
+                Ok(result) => result.map(|_| true),
+                Err(_) => Ok(false),
+            }
+        } else {
+            self.file.readable().await.map(|_| true)
+        }
+    }
+
     /// Get the value of GPIO lines
     ///
     /// The values can only be read if the lines have previously been requested as inputs
     /// or outputs using the [Chip::request_lines] method with [Options::input] or with
     /// [Options::output].
+    ///
+    /// Only the values which fits into given buffer are read, while the values in buffer
+    /// above the number of requested lines are cleared (reset to `false` for booleans).
     pub async fn get_values<T: AsValuesMut + Send + 'static>(&self, mut values: T) -> Result<T> {
         let fd = self.file.as_raw_fd();
         let info = self.info.clone();
         asyncify(move || info.get_values(fd, &mut values).map(|_| values)).await
     }
+
+    /// Get the value of GPIO lines without blocking task
+    ///
+    /// Same as [Lines::get_values] but performs the ioctl directly on the current thread.
+    /// Reading values never blocks, so it is safe to call from async context, while it
+    /// saves the hop to the blocking thread pool. Useful for reading values in tight loops.
+    pub fn get_values_now<T: AsValuesMut>(&self, mut values: T) -> Result<T> {
+        self.info.get_values(self.file.as_raw_fd(), &mut values)?;
+        Ok(values)
+    }
+
+    /// Get the value of single GPIO line
+    ///
+    /// The line is given by offset and should be one of requested lines.
+    pub async fn get_value(&self, line: LineId) -> Result<bool> {
+        let fd = self.file.as_raw_fd();
+        let info = self.info.clone();
+        asyncify(move || info.get_value(fd, line)).await
+    }
 }
 
 impl Lines<Input> {
@@ -116,16 +315,211 @@ impl Lines<Input> {
     pub async fn read_event(&mut self) -> Result<Event> {
         #[cfg(not(feature = "v2"))]
         {
-            todo!();
+            let fd = self.file.as_raw_fd();
+
+            self.file.read_with(|_| self.info.read_event(fd)).await
+        }
+
+        #[cfg(feature = "v2")]
+        {
+            // Partially read event is kept in buffer until the rest of it arrives
+            let mut events = Vec::with_capacity(1);
+
+            self.read_events(&mut events, 1).await?;
+
+            events
+                .pop()
+                .ok_or_else(|| std::io::ErrorKind::UnexpectedEof.into())
+        }
+    }
+
+    /// Read up to `max` GPIO events at once appending it to `buf`
+    ///
+    /// Waits until at least one event available and returns the number of events which was read.
+    /// With ABI v2 events are read using single syscall which reduces overhead under load.
+    pub async fn read_events(&mut self, buf: &mut Vec<Event>, max: usize) -> Result<usize> {
+        #[cfg(not(feature = "v2"))]
+        {
+            let start = buf.len();
+
+            buf.push(self.read_event().await?);
+
+            while buf.len() - start < max && self.wait_readable(Some(Duration::ZERO)).await? {
+                buf.push(self.read_event().await?);
+            }
+
+            Ok(buf.len() - start)
+        }
+
+        #[cfg(feature = "v2")]
+        {
+            let mut data = vec![0; self.events.read_len(max)];
+
+            loop {
+                let len = self.file.read(&mut data).await?;
+                let count = self.events.decode(
+                    &data[..len],
+                    self.info.index(),
+                    self.info.event_clock(),
+                    buf,
+                )?;
+
+                if count > 0 || len == 0 {
+                    return Ok(count);
+                }
+            }
+        }
+    }
+
+    /// Wait for edge on single line
+    ///
+    /// Reads events discarding ones of other lines or of opposite edge and returns
+    /// the first matching event. Fails when the line is not requested or its edge
+    /// detection does not cover the edge.
+    pub async fn wait_edge(&mut self, line: LineId, edge: Edge) -> Result<Event> {
+        self.check_edge(line, edge)?;
+
+        loop {
+            let event = self.read_event().await?;
+
+            if event.offset == line && event.edge == edge {
+                return Ok(event);
+            }
+        }
+    }
+
+    /// Wait for edge on single line up to timeout
+    ///
+    /// Same as [Lines::wait_edge] but returns `None` when timeout elapsed before
+    /// matching event arrived.
+    pub async fn wait_edge_timeout(
+        &mut self,
+        line: LineId,
+        edge: Edge,
+        timeout: Duration,
+    ) -> Result<Option<Event>> {
+        match future::timeout(timeout, self.wait_edge(line, edge)).await {
+            Ok(result) => result.map(Some),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Read GPIO event along with values of all requested lines
+    ///
+    /// The values are read right after the event, so the lines may change in between.
+    /// Useful for protocol decoders which need state of other lines at each edge.
+    pub async fn read_event_with_values(&mut self) -> Result<(Event, Values)> {
+        let event = self.read_event().await?;
+        let values = self.get_values(Values::default()).await?;
+
+        Ok((event, values))
+    }
+
+    /// Record GPIO events
+    ///
+    /// Reads events and writes it as JSON lines (see [EventRecord]) until reading or writing fails.
+    /// Available with `json` feature.
+    #[cfg(feature = "json")]
+    pub async fn record_events(
+        &mut self,
+        mut writer: impl async_std::io::Write + Unpin,
+    ) -> Result<()> {
+        use async_std::io::WriteExt;
+
+        loop {
+            let record = EventRecord::from(self.read_event().await?);
+
+            let mut line = serde_json::to_vec(&record).map_err(std::io::Error::from)?;
+            line.push(b'\n');
+
+            writer.write_all(&line).await?;
+            writer.flush().await?;
+        }
+    }
+
+    /// Read GPIO events collecting per-line statistics
+    ///
+    /// Useful to detect a noisy line which dominates the events of request.
+    pub fn events_with_stats(&mut self) -> EventsWithStats<'_> {
+        let stats = EventStats::new(self.info.lines().len());
+
+        EventsWithStats { lines: self, stats }
+    }
+}
+
+/// GPIO events reader which collects per-line statistics
+///
+/// Use [Lines::events_with_stats] to create it.
+pub struct EventsWithStats<'a> {
+    lines: &'a mut Lines<Input>,
+    stats: EventStats,
+}
+
+impl EventsWithStats<'_> {
+    /// Read GPIO event and account it
+    pub async fn read_event(&mut self) -> Result<Event> {
+        let event = self.lines.read_event().await?;
+        self.stats.record(&event);
+        Ok(event)
+    }
+
+    /// Get collected statistics
+    pub fn stats(&self) -> &EventStats {
+        &self.stats
+    }
+
+    /// Reset collected statistics
+    pub fn reset_stats(&mut self) {
+        self.stats.reset();
+    }
+}
+
+/// Stream of GPIO events
+///
+/// Events are read only when stream is polled, so dropping it never loses an event.
+impl Stream for Lines<Input> {
+    type Item = Result<Event>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        #[cfg(not(feature = "v2"))]
+        {
+            let fd = this.file.as_raw_fd();
+            let info = &this.info;
+
+            this.file
+                .poll_read_with(cx, |_| info.read_event(fd))
+                .map(Some)
         }
 
         #[cfg(feature = "v2")]
         {
-            let mut event = gpiod_core::RawEvent::default();
+            use std::io::Read;
+
+            let mut events = Vec::with_capacity(1);
 
-            gpiod_core::check_size(self.file.read(event.as_mut()).await?, &event)?;
+            loop {
+                let mut data = vec![0; this.events.read_len(1)];
 
-            event.as_event(self.info.index())
+                let len = ready!(this
+                    .file
+                    .poll_read_with(cx, |mut file| Ok(file.read(&mut data)?)))?;
+
+                if len == 0 {
+                    return Poll::Ready(None);
+                }
+
+                if this.events.decode(
+                    &data[..len],
+                    this.info.index(),
+                    this.info.event_clock(),
+                    &mut events,
+                )? > 0
+                {
+                    return Poll::Ready(events.pop().map(Ok));
+                }
+            }
         }
     }
 }
@@ -135,21 +529,226 @@ impl Lines<Output> {
     ///
     /// The value can only be set if the lines have previously been requested as outputs
     /// using the [Chip::request_lines] with [Options::output].
+    ///
+    /// New values are applied immediately, there is no need to call [Lines::flush].
     pub async fn set_values<T: AsValues + Send + 'static>(&self, values: T) -> Result<()> {
         let fd = self.file.as_raw_fd();
         let info = self.info.clone();
         asyncify(move || info.set_values(fd, values)).await
     }
+
+    /// Set the value of single GPIO line
+    ///
+    /// The line is given by offset and should be one of requested lines.
+    /// Other lines of the request are not changed.
+    pub async fn set_value(&self, line: LineId, value: bool) -> Result<()> {
+        let fd = self.file.as_raw_fd();
+        let info = self.info.clone();
+        asyncify(move || info.set_value(fd, line, value)).await
+    }
+
+    /// Set values of GPIO lines for a given duration and then set inverse values
+    ///
+    /// Only lines which has values in the mask are affected. Inverse values are written even
+    /// when sleep is interrupted.
+    ///
+    /// Timing is best-effort and not real-time guaranteed.
+    pub async fn pulse<T: AsValues>(&self, values: T, duration: Duration) -> Result<()> {
+        let fd = self.file.as_raw_fd();
+        let values = values.convert::<Values>();
+
+        self.set_values(values).await?;
+        // restores values synchronously when future is dropped before completion
+        let guard = self.info.restore_guard(fd, values)?;
+
+        task::sleep(duration).await;
+
+        let info = self.info.clone();
+        let restore = guard.values();
+        asyncify(move || info.set_values(fd, restore)).await?;
+        guard.disarm();
+
+        Ok(())
+    }
+
+    /// Toggle values of all GPIO lines
+    ///
+    /// The current values are read and written back inverted.
+    pub async fn toggle(&self) -> Result<()> {
+        let fd = self.file.as_raw_fd();
+        let info = self.info.clone();
+        asyncify(move || info.toggle_values(fd, None)).await
+    }
+
+    /// Toggle value of single GPIO line
+    ///
+    /// Other lines of the request are not changed.
+    pub async fn toggle_line(&self, line: LineId) -> Result<()> {
+        let fd = self.file.as_raw_fd();
+        let info = self.info.clone();
+        asyncify(move || info.toggle_values(fd, Some(line))).await
+    }
+
+    /// Write a sequence of values to a single GPIO line
+    ///
+    /// Each value is held for `bit_duration` and the line keeps the last value afterwards.
+    /// Other lines of the request are not changed.
+    ///
+    /// Timing is done by sleeping between ioctl calls, so it is subject to scheduling jitter.
+    /// Switching is compensated to avoid accumulating drift, but this is still unsuitable for
+    /// protocols which require precise timing.
+    pub async fn write_pattern(
+        &self,
+        line: LineId,
+        pattern: &[bool],
+        bit_duration: Duration,
+    ) -> Result<()> {
+        let id = self.info.index().find(line)?;
+        let start = Instant::now();
+
+        for (n, &value) in pattern.iter().enumerate() {
+            let deadline = start + bit_duration * n as u32;
+            task::sleep(deadline.saturating_duration_since(Instant::now())).await;
+
+            self.set_values(Values::default().with(id, Some(value)))
+                .await?;
+        }
+
+        if !pattern.is_empty() {
+            let deadline = start + bit_duration * pattern.len() as u32;
+            task::sleep(deadline.saturating_duration_since(Instant::now())).await;
+        }
+
+        Ok(())
+    }
+
+    /// Flush pending values
+    ///
+    /// Values are not buffered, each [Lines::set_values] call issues ioctl which changes
+    /// lines immediately, so it is synchronous and this method does nothing.
+    /// It exists for symmetry with buffered IO and will be useful if writes get batched.
+    pub async fn flush(&self) -> Result<()> {
+        Ok(())
+    }
 }
 
+/// Shift register (74HC595-like) driver built on output lines
+///
+/// It drives data, clock and latch lines of the request: each bit is set on data line
+/// and shifted in by rising edge of clock, then latch is pulsed to transfer shifted bits
+/// to register outputs. Use [ShiftOut::new] to create it.
+///
+/// The timing relies on sleeping between ioctl calls, so the actual clock period is longer than
+/// configured and jittery. This is fine for shift registers which have no timing constraints
+/// except minimal pulse widths, but unsuitable for protocols which require precise timing.
+pub struct ShiftOut<'a> {
+    lines: &'a Lines<Output>,
+    data: BitId,
+    clock: BitId,
+    latch: BitId,
+    order: BitOrder,
+    half_period: Duration,
+}
+
+impl<'a> ShiftOut<'a> {
+    /// Create shift register driver using offsets of data, clock and latch lines
+    ///
+    /// All of the lines should be requested as outputs by the given request.
+    pub fn new(
+        lines: &'a Lines<Output>,
+        data: LineId,
+        clock: LineId,
+        latch: LineId,
+    ) -> Result<Self> {
+        let index = lines.info.index();
+
+        Ok(Self {
+            lines,
+            data: index.find(data)?,
+            clock: index.find(clock)?,
+            latch: index.find(latch)?,
+            order: BitOrder::default(),
+            half_period: Duration::ZERO,
+        })
+    }
+
+    /// Configure order of bits when writing bytes (MSB first by default)
+    pub fn bit_order(mut self, order: BitOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Configure minimal duration of clock and latch pulses (zero by default)
+    pub fn half_period(mut self, half_period: Duration) -> Self {
+        self.half_period = half_period;
+        self
+    }
+
+    /// Shift bits in given order and latch it
+    pub async fn write_bits(&self, bits: &[bool]) -> Result<()> {
+        for &bit in bits {
+            self.set(
+                Values::default()
+                    .with(self.data, Some(bit))
+                    .with(self.clock, Some(false)),
+            )
+            .await?;
+            self.set(Values::default().with(self.clock, Some(true)))
+                .await?;
+        }
+
+        self.set(Values::default().with(self.clock, Some(false)))
+            .await?;
+        self.set(Values::default().with(self.latch, Some(true)))
+            .await?;
+        self.set(Values::default().with(self.latch, Some(false)))
+            .await
+    }
+
+    /// Shift byte and latch it
+    pub async fn write_byte(&self, byte: u8) -> Result<()> {
+        self.write_bits(&self.order.bits(byte)).await
+    }
+
+    /// Shift bytes (for chained registers) and latch it
+    pub async fn write_bytes(&self, bytes: &[u8]) -> Result<()> {
+        let bits = bytes
+            .iter()
+            .flat_map(|byte| self.order.bits(*byte))
+            .collect::<Vec<_>>();
+
+        self.write_bits(&bits).await
+    }
+
+    async fn set(&self, values: Values) -> Result<()> {
+        self.lines.set_values(values).await?;
+
+        if !self.half_period.is_zero() {
+            task::sleep(self.half_period).await;
+        }
+
+        Ok(())
+    }
+}
+
+/// Paths of chips which cannot be opened along with reasons
+///
+/// See [Chip::open_devices] and [Chip::all_with_failures].
+pub type OpenFailures = Vec<(PathBuf, Error)>;
+
 /// A Linux chardev GPIO chip interface
 ///
 /// It can be used to get information about the chip and lines and
 /// to request GPIO lines that can be used as inputs or outputs.
+///
+/// Chip is [Send] and [Sync], all methods requires shared reference only,
+/// so it can be shared between threads via [std::sync::Arc] without any locking.
 pub struct Chip {
     info: Arc<Internal<ChipInfo>>,
     // wrap file to call close on drop
     file: File,
+    path: PathBuf,
+    rdev: u64,
 }
 
 impl Deref for Chip {
@@ -171,8 +770,82 @@ const O_NONBLOCK: i32 = 2048;
 impl Chip {
     /// Create a new GPIO chip interface using path
     pub async fn new(path: impl AsRef<Path>) -> Result<Chip> {
-        let path = path.as_ref();
+        Self::open(path.as_ref(), true, true).await
+    }
+
+    /// Create a GPIO chip interface using path opened for reading only
+    ///
+    /// Useful for monitoring tools which have only read permission on the device
+    /// (ex. due to restrictive udev rules). Chip and line info can be read as usual,
+    /// but requesting outputs fails with [std::io::ErrorKind::PermissionDenied] error.
+    pub async fn open_readonly(path: impl AsRef<Path>) -> Result<Chip> {
+        Self::open(path.as_ref(), true, false).await
+    }
+
+    /// Open GPIO chip using its label
+    ///
+    /// Unlike chip names the labels (ex. `pinctrl-bcm2711`) are stable across boots.
+    /// Chips which cannot be opened are skipped. Fails when no chips or more than one chip
+    /// has such label.
+    pub async fn by_label(label: &str) -> Result<Chip> {
+        let (chips, _) = Self::open_devices(Self::list_devices().await?).await;
+
+        select_chip_by_label(chips, label)
+    }
+
+    /// Create a new GPIO chip interface using chip number
+    ///
+    /// Opens `/dev/gpiochipN` device, so [ChipInfo::number] of resulting chip equals `number`.
+    pub async fn open_by_number(number: u32) -> Result<Chip> {
+        Self::new(format!("gpiochip{}", number)).await
+    }
+
+    /// Create a new GPIO chip interface using path without checking GPIO subsystem
+    ///
+    /// Unlike [Chip::new] it only checks that path is a character device, but does not check
+    /// that the device belongs to GPIO subsystem using sysfs. This is faster and works
+    /// in minimal containers without `/sys`, but opening a non-GPIO device will fail later
+    /// (or even do something unexpected when device accepts the same ioctl codes),
+    /// so use it only with trusted paths.
+    pub async fn open_unchecked(path: impl AsRef<Path>) -> Result<Chip> {
+        Self::open(path.as_ref(), false, true).await
+    }
 
+    /// Create a GPIO chip interface using already opened file descriptor
+    ///
+    /// Useful when the process cannot open `/dev` itself, for example when the descriptor
+    /// is passed by supervisor or sandbox. The ownership of descriptor is taken, so it will
+    /// be closed when chip dropped (or when this function fails). The descriptor is switched
+    /// to non-blocking mode.
+    ///
+    /// Like [Chip::open_unchecked] it only checks that descriptor refers to a character
+    /// device. The [Chip::path] is derived from the chip name.
+    pub async fn from_fd(fd: RawFd) -> Result<Chip> {
+        // take ownership first to close fd on failure
+        let file = unsafe { std::fs::File::from_raw_fd(fd) };
+        let metadata = file.metadata()?;
+
+        /* Is it a character device? */
+        if !metadata.file_type().is_char_device() {
+            return Err(invalid_input("File is not character device"));
+        }
+
+        set_nonblock(file.as_raw_fd())?;
+
+        let file = File::from_fd(file.into_raw_fd())?;
+
+        let fd = file.as_raw_fd();
+        let info = Arc::new(asyncify(move || Internal::<ChipInfo>::from_fd(fd)).await?);
+
+        Ok(Chip {
+            path: Path::new("/dev").join(info.name()),
+            info,
+            file,
+            rdev: metadata.rdev(),
+        })
+    }
+
+    async fn open(path: &Path, check_subsystem: bool, write: bool) -> Result<Chip> {
         #[allow(unused_assignments)]
         let mut full_path = None;
 
@@ -186,18 +859,23 @@ impl Chip {
         let file = File::from_file(
             OpenOptions::new()
                 .read(true)
-                .write(true)
+                .write(write)
                 .custom_flags(O_NONBLOCK)
                 .open(path)
                 .await?,
         )?;
 
-        Chip::check_device(path).await?;
+        let rdev = Chip::check_device(path, check_subsystem).await?;
 
         let fd = file.as_raw_fd();
         let info = Arc::new(asyncify(move || Internal::<ChipInfo>::from_fd(fd)).await?);
 
-        Ok(Chip { info, file })
+        Ok(Chip {
+            info,
+            file,
+            path: path.to_owned(),
+            rdev,
+        })
     }
 
     /// List all found chips
@@ -207,7 +885,7 @@ impl Chip {
 
         while let Some(ent) = dir.next().await {
             let path = ent?.path();
-            if Self::check_device(&path).await.is_ok() {
+            if Self::check_device(&path, true).await.is_ok() {
                 devices.push(path);
             }
         }
@@ -215,8 +893,69 @@ impl Chip {
         Ok(devices)
     }
 
-    async fn check_device(path: &Path) -> Result<()> {
-        let metadata = fs::symlink_metadata(&path).await?;
+    /// Open all found chips
+    ///
+    /// Chips which cannot be opened are skipped (use [Chip::all_with_failures] to get
+    /// the reasons). The chips are sorted by number in descending order.
+    pub async fn all() -> Result<Vec<Chip>> {
+        Ok(Self::all_with_failures().await?.0)
+    }
+
+    /// Open all found chips collecting failures separately
+    ///
+    /// Like [Chip::all] but chips which cannot be opened are returned along with reason.
+    pub async fn all_with_failures() -> Result<(Vec<Chip>, OpenFailures)> {
+        let (mut chips, failures) = Self::open_devices(Self::list_devices().await?).await;
+
+        sort_chips(&mut chips);
+
+        Ok((chips, failures))
+    }
+
+    /// Open chips using paths collecting failures separately
+    ///
+    /// Unlike opening chips one by one it does not stop on first failure, so chips which
+    /// cannot be opened (ex. due to permissions) are returned along with reason.
+    pub async fn open_devices<P: AsRef<Path>>(
+        paths: impl IntoIterator<Item = P>,
+    ) -> (Vec<Chip>, OpenFailures) {
+        let mut chips = Vec::new();
+        let mut failures = Vec::new();
+
+        for path in paths {
+            match Self::new(&path).await {
+                Ok(chip) => chips.push(chip),
+                Err(error) => failures.push((path.as_ref().to_owned(), error)),
+            }
+        }
+
+        (chips, failures)
+    }
+
+    /// Stream all found chips
+    ///
+    /// Unlike [Chip::list_devices] it yields chips incrementally as found while scanning.
+    /// The stream ends after the first error.
+    pub fn stream_devices() -> impl Stream<Item = Result<PathBuf>> + Send {
+        use futures_util::{stream, TryStreamExt};
+
+        stream::once(fs::read_dir("/dev"))
+            .map_ok(|dir| {
+                stream::try_unfold(dir, |mut dir| async move {
+                    while let Some(ent) = dir.next().await {
+                        let path = ent?.path();
+                        if Self::check_device(&path, true).await.is_ok() {
+                            return Ok(Some((path, dir)));
+                        }
+                    }
+                    Ok(None)
+                })
+            })
+            .try_flatten()
+    }
+
+    async fn check_device(path: &Path, check_subsystem: bool) -> Result<u64> {
+        let metadata = fs::symlink_metadata(path).await?;
 
         /* Is it a character device? */
         if !metadata.file_type().is_char_device() {
@@ -225,6 +964,10 @@ impl Chip {
 
         let rdev = metadata.rdev();
 
+        if !check_subsystem {
+            return Ok(rdev);
+        }
+
         /* Is the device associated with the GPIO subsystem? */
         if fs::canonicalize(format!(
             "/sys/dev/char/{}:{}/subsystem",
@@ -237,7 +980,51 @@ impl Chip {
             return Err(invalid_input("Character device is not a GPIO"));
         }
 
-        Ok(())
+        Ok(rdev)
+    }
+
+    /// Get path of opened chip device (ex. `/dev/gpiochip0`)
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Get device number of chip as `(major, minor)` pair
+    pub fn device_number(&self) -> (u64, u64) {
+        (major(self.rdev), minor(self.rdev))
+    }
+
+    /// Probe the version of GPIO ABI supported by kernel
+    ///
+    /// Unlike [ChipInfo::abi] which reports ABI selected at compile time, the kernel is asked,
+    /// so the binary built with `v2` feature can detect that it runs on older kernel which
+    /// supports ABI v1 only. When kernel supports both ABIs [AbiVersion::V2] is reported.
+    /// The chip without lines cannot be used to detect older kernel, so it fails in that case.
+    pub async fn abi_version(&self) -> Result<AbiVersion> {
+        let fd = self.file.as_raw_fd();
+        let info = self.info.clone();
+        asyncify(move || info.kernel_abi(fd)).await
+    }
+
+    /// Create another interface to the same GPIO chip
+    ///
+    /// The chip descriptor is duplicated, so the clone refers to the same chip and
+    /// can request lines independently from the original one.
+    pub fn try_clone(&self) -> Result<Chip> {
+        Ok(Chip {
+            info: self.info.clone(),
+            file: self.file.try_clone()?,
+            path: self.path.clone(),
+            rdev: self.rdev,
+        })
+    }
+
+    /// Prepend prefix to consumer of each request made via this chip
+    ///
+    /// Useful to make all lines requested by an application attributable to it.
+    /// The resulting consumer string will be truncated to fit into kernel limit (31 bytes).
+    pub fn with_consumer_prefix(mut self, prefix: impl Into<String>) -> Self {
+        Arc::make_mut(&mut self.info).set_consumer_prefix(prefix);
+        self
     }
 
     /// Request the info of a specific GPIO line.
@@ -247,10 +1034,172 @@ impl Chip {
         asyncify(move || info.line_info(fd, line)).await
     }
 
+    /// Request the info of all GPIO lines
+    ///
+    /// The lines are swept in single background task.
+    /// Fails when info of any line cannot be read, see [Chip::line_info_all_lenient].
+    pub async fn line_info_all(&self) -> Result<Vec<LineInfo>> {
+        let fd = self.file.as_raw_fd();
+        let info = self.info.clone();
+        asyncify(move || info.line_info_all(fd)).await
+    }
+
+    /// Request the info of all GPIO lines keeping errors per line
+    ///
+    /// Unlike [Chip::line_info_all] the failure of single line does not abort the sweep.
+    pub async fn line_info_all_lenient(&self) -> Vec<Result<LineInfo>> {
+        let fd = self.file.as_raw_fd();
+        let info = self.info.clone();
+        asyncify(move || info.line_info_all_lenient(fd)).await
+    }
+
+    /// Probe features supported by a specific GPIO line
+    ///
+    /// The line must be unused. Features of input lines are probed by short requests which
+    /// released immediately, while output lines is never requested to avoid changing its value,
+    /// so only configured features are reported for it (see [LineCapabilities]).
+    pub async fn capabilities(&self, line: LineId) -> Result<LineCapabilities> {
+        let fd = self.file.as_raw_fd();
+        let info = self.info.clone();
+        asyncify(move || info.capabilities(fd, line)).await
+    }
+
+    /// Find the offset of GPIO line by name
+    ///
+    /// The names of lines will be scanned at first call and cached, so the first call costs
+    /// one ioctl per line of chip while next calls does not touch the device.
+    /// Returns `None` when chip has no line with such name.
+    pub async fn find_line(&self, name: impl AsRef<str>) -> Result<Option<LineId>> {
+        let fd = self.file.as_raw_fd();
+        let info = self.info.clone();
+        let name = name.as_ref().to_owned();
+        asyncify(move || info.find_line(fd, &name)).await
+    }
+
+    /// Find the offsets of GPIO lines by names
+    ///
+    /// Same as [Chip::find_line] but for many names at once. The results are in order of names.
+    pub async fn find_lines_by_name(
+        &self,
+        names: &[impl AsRef<str>],
+    ) -> Result<Vec<Option<LineId>>> {
+        let fd = self.file.as_raw_fd();
+        let info = self.info.clone();
+        let names = names
+            .iter()
+            .map(|name| name.as_ref().to_owned())
+            .collect::<Vec<_>>();
+        asyncify(move || info.find_lines_by_name(fd, &names)).await
+    }
+
+    /// Find GPIO lines which names matches pattern
+    ///
+    /// Unlike [Chip::find_line] returns all matched lines with names ordered by offsets.
+    /// When line names are not unique only the first line with such name is considered.
+    pub async fn find_lines(
+        &self,
+        pattern: impl AsRef<str>,
+        mode: NameMatch,
+    ) -> Result<Vec<(LineId, String)>> {
+        let fd = self.file.as_raw_fd();
+        let info = self.info.clone();
+        let pattern = pattern.as_ref().to_owned();
+        asyncify(move || info.find_lines(fd, &pattern, mode)).await
+    }
+
+    /// Find GPIO lines which names matches glob pattern (ex. `led*`)
+    ///
+    /// Same as [Chip::find_lines] with [NameMatch::Glob].
+    pub async fn find_lines_matching(
+        &self,
+        pattern: impl AsRef<str>,
+    ) -> Result<Vec<(LineId, String)>> {
+        self.find_lines(pattern, NameMatch::Glob).await
+    }
+
+    /// Start watching info changes of a specific GPIO line
+    ///
+    /// Returns current line info. The changes such as requesting, releasing or reconfiguring
+    /// the line by any process can be read using [Chip::read_line_info_change] then.
+    pub async fn watch_line_info(&self, line: LineId) -> Result<LineInfo> {
+        let fd = self.file.as_raw_fd();
+        let info = self.info.clone();
+        asyncify(move || info.watch_line_info(fd, line)).await
+    }
+
+    /// Stop watching info changes of a specific GPIO line
+    pub async fn unwatch_line_info(&self, line: LineId) -> Result<()> {
+        let fd = self.file.as_raw_fd();
+        let info = self.info.clone();
+        asyncify(move || info.unwatch_line_info(fd, line)).await
+    }
+
+    /// Read info change of watched GPIO lines
+    ///
+    /// Waits until any of lines watched using [Chip::watch_line_info] changes.
+    pub async fn read_line_info_change(&self) -> Result<LineChange> {
+        let mut change = gpiod_core::RawInfoChange::default();
+
+        gpiod_core::check_size(self.file.read_shared(change.as_mut()).await?, &change)?;
+
+        change.as_info_change()
+    }
+
+    /// Stream info changes of watched GPIO lines
+    ///
+    /// Yields changes of lines watched using [Chip::watch_line_info].
+    /// The stream ends after the first error.
+    pub fn line_info_changes(&self) -> impl Stream<Item = Result<LineChange>> + Send + '_ {
+        futures_util::stream::try_unfold(self, |chip| async move {
+            let change = chip.read_line_info_change().await?;
+            Ok(Some((change, chip)))
+        })
+    }
+
+    /// Wait until a specific GPIO line is released
+    ///
+    /// Watches the info changes of line and returns `true` when line is unused or has been
+    /// released by its consumer, so it can likely be requested. Returns `false` when timeout
+    /// elapsed. The `None` timeout means waiting infinitely.
+    ///
+    /// The line must not be watched already. Info changes of other watched lines which
+    /// arrives while waiting will be discarded.
+    pub async fn wait_line_free(&self, line: LineId, timeout: Option<Duration>) -> Result<bool> {
+        let result = if self.watch_line_info(line).await?.used {
+            if let Some(timeout) = timeout {
+                match future::timeout(timeout, self.wait_line_released(line)).await {
+                    Ok(result) => result,
+                    Err(_) => Ok(false),
+                }
+            } else {
+                self.wait_line_released(line).await
+            }
+        } else {
+            Ok(true)
+        };
+
+        self.unwatch_line_info(line).await?;
+
+        result
+    }
+
+    async fn wait_line_released(&self, line: LineId) -> Result<bool> {
+        loop {
+            let change = self.read_line_info_change().await?;
+
+            if change.line == line && change.kind == LineChangeKind::Released {
+                return Ok(true);
+            }
+        }
+    }
+
     /// Request the GPIO chip to configure the lines passed as argument as inputs or outputs
     ///
     /// Calling this operation is a precondition to being able to set the state of the GPIO lines.
     /// All the lines passed in one request must share the configured options such as active state, edge detect, GPIO bias, output drive and consumer string.
+    ///
+    /// Lines created using [Options::input_named] or [Options::output_named] will be resolved
+    /// by names here, the unknown names results in [std::io::ErrorKind::InvalidInput] error.
     pub async fn request_lines<Direction: DirectionType>(
         &self,
         options: Options<Direction, impl AsRef<[LineId]>, impl AsRef<str>>,
@@ -273,6 +1222,206 @@ impl Chip {
             dir: PhantomData,
             info,
             file,
+            #[cfg(feature = "v2")]
+            events: Default::default(),
         })
     }
+
+    /// Request each GPIO line separately with its own options
+    ///
+    /// The kernel applies single consumer string per request, so requesting lines individually
+    /// is the only way to label each line distinctly. The lines given to options are ignored.
+    ///
+    /// Note that each request holds its own file descriptor and the returned handles
+    /// are independent, so values of such lines cannot be get or set atomically.
+    pub async fn request_lines_individually<Direction: DirectionType>(
+        &self,
+        requests: &[(
+            LineId,
+            Options<Direction, impl AsRef<[LineId]>, impl AsRef<str>>,
+        )],
+    ) -> Result<Vec<Lines<Direction>>> {
+        let mut lines = Vec::with_capacity(requests.len());
+
+        for (line, options) in requests {
+            lines.push(
+                self.request_lines(options.to_owned().lines([*line]))
+                    .await?,
+            );
+        }
+
+        Ok(lines)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[cfg(feature = "v2")]
+    #[async_std::test]
+    async fn read_event_split() {
+        use gpiod_core::RawEvent;
+        use std::{io::Write, os::unix::net::UnixStream};
+
+        let (reader, mut writer) = UnixStream::pair().unwrap();
+        let options = Options::input([3, 5]).edge(EdgeDetect::Both);
+
+        let mut lines = Lines::<Input> {
+            dir: PhantomData,
+            info: Arc::new(Internal::<ValuesInfo>::new("gpiochip0", &options).unwrap()),
+            file: File::from_fd(reader.into_raw_fd()).unwrap(),
+            events: Default::default(),
+        };
+
+        let mut data = Vec::new();
+        for offset in [5, 3] {
+            let mut event = RawEvent::default();
+            event.offset = offset;
+            // rising edge as defined by kernel ABI v2
+            event.id = 1;
+            data.extend_from_slice(event.as_mut());
+        }
+
+        // first event arrives in two parts, so the next read also gets the head of second event
+        writer.write_all(&data[..10]).unwrap();
+        let rest = data[10..].to_vec();
+        let inject = async move {
+            task::sleep(Duration::from_millis(20)).await;
+            writer.write_all(&rest).unwrap();
+            writer
+        };
+
+        let (event, _writer) = futures_util::future::join(lines.read_event(), inject).await;
+        let event = event.unwrap();
+        assert_eq!(event.offset, 5);
+        assert_eq!(event.edge, Edge::Rising);
+
+        let event = lines.read_event().await.unwrap();
+        assert_eq!(event.offset, 3);
+    }
+
+    #[cfg(feature = "v2")]
+    #[async_std::test]
+    async fn wait_edge_injected() {
+        use gpiod_core::RawEvent;
+        use std::{io::Write, os::unix::net::UnixStream};
+
+        // Event ids as defined by kernel ABI v2
+        const RISING_EDGE: u32 = 1;
+        const FALLING_EDGE: u32 = 2;
+
+        let (reader, mut writer) = UnixStream::pair().unwrap();
+        let options = Options::input([3, 5]).edge(EdgeDetect::Both);
+
+        let mut lines = Lines::<Input> {
+            dir: PhantomData,
+            info: Arc::new(Internal::<ValuesInfo>::new("gpiochip0", &options).unwrap()),
+            file: File::from_fd(reader.into_raw_fd()).unwrap(),
+            events: Default::default(),
+        };
+
+        for (offset, id) in [(3, RISING_EDGE), (5, FALLING_EDGE), (5, RISING_EDGE)] {
+            let mut event = RawEvent::default();
+            event.offset = offset;
+            event.id = id;
+            writer.write_all(event.as_mut()).unwrap();
+        }
+
+        let event = lines.wait_edge(5, Edge::Rising).await.unwrap();
+        assert_eq!(event.offset, 5);
+        assert_eq!(event.line, 1);
+        assert_eq!(event.edge, Edge::Rising);
+
+        let timeout = Duration::from_millis(20);
+        assert!(lines
+            .wait_edge_timeout(3, Edge::Falling, timeout)
+            .await
+            .unwrap()
+            .is_none());
+
+        let error = lines.wait_edge(7, Edge::Rising).await.err().unwrap();
+        assert!(matches!(error, Error::LineNotFound(_)));
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn chip_send_sync() {
+        assert_send_sync::<Chip>();
+    }
+
+    #[async_std::test]
+    #[ignore = "requires GPIO chips"]
+    async fn all_chips() {
+        let chips = Chip::all().await.unwrap();
+        let (opened, _) = Chip::open_devices(Chip::list_devices().await.unwrap()).await;
+
+        assert_eq!(chips.len(), opened.len());
+        assert!(chips
+            .windows(2)
+            .all(|pair| pair[0].number() >= pair[1].number()));
+    }
+
+    #[async_std::test]
+    async fn open_devices_failures() {
+        let paths = ["/dev/null", "/nonexistent/gpiochip9"];
+
+        let (chips, failures) = Chip::open_devices(paths).await;
+
+        assert!(chips.is_empty());
+        assert_eq!(
+            failures
+                .iter()
+                .map(|(path, _)| path.to_str().unwrap())
+                .collect::<Vec<_>>(),
+            paths
+        );
+        assert_eq!(failures[1].1.kind(), std::io::ErrorKind::NotFound);
+    }
+
+    #[async_std::test]
+    #[ignore = "requires GPIO chip readable by user"]
+    async fn chip_open_readonly() {
+        let path = Chip::list_devices()
+            .await
+            .unwrap()
+            .into_iter()
+            .next()
+            .unwrap();
+        let chip = Chip::open_readonly(path).await.unwrap();
+
+        chip.line_info(0).await.unwrap();
+
+        let error = chip
+            .request_lines(Options::output([0]))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(error.kind(), std::io::ErrorKind::PermissionDenied);
+    }
+
+    #[async_std::test]
+    async fn chip_from_fd_not_device() {
+        let (reader, _writer) = std::os::unix::net::UnixStream::pair().unwrap();
+
+        let error = Chip::from_fd(reader.into_raw_fd()).await.err().unwrap();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn lines_send_sync() {
+        assert_send_sync::<Lines<Input>>();
+        assert_send_sync::<Lines<Output>>();
+    }
+
+    #[async_std::test]
+    async fn stream_devices() {
+        use futures_util::TryStreamExt;
+
+        let streamed: Vec<_> = Chip::stream_devices().try_collect().await.unwrap();
+
+        assert_eq!(streamed, Chip::list_devices().await.unwrap());
+    }
 }
@@ -21,9 +21,8 @@ fn genman() -> Result<()> {
         Ok(())
     }
 
-    let out_dir = std::path::PathBuf::from(
-        std::env::var_os("OUT_DIR").ok_or_else(|| std::io::ErrorKind::NotFound)?,
-    );
+    let out_dir =
+        std::path::PathBuf::from(std::env::var_os("OUT_DIR").ok_or(std::io::ErrorKind::NotFound)?);
 
     let cmd = <Args as clap::CommandFactory>::command();
 
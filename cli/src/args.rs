@@ -34,10 +34,18 @@ pub struct Args {
 #[derive(clap::Parser)]
 pub enum Cmd {
     /// List GPIO devices
-    Detect,
+    Detect {
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: Format,
+    },
 
     /// Get info about GPIO devices
     Info {
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: Format,
+
         /// GPIO chip paths or names (ex. gpiochip0)
         #[arg(value_parser)]
         chip: Vec<String>,
@@ -57,6 +65,14 @@ pub enum Cmd {
         #[arg(short, long, value_parser, default_value = "gpioget")]
         consumer: String,
 
+        /// Label values by line names
+        #[arg(short, long)]
+        labeled: bool,
+
+        /// Show physical levels along with logical values
+        #[arg(short, long)]
+        physical: bool,
+
         /// GPIO chip path or name (ex. gpiochip0)
         #[arg(value_parser)]
         chip: std::path::PathBuf,
@@ -84,11 +100,19 @@ pub enum Cmd {
         #[arg(short, long, value_parser, default_value = "gpioset")]
         consumer: String,
 
+        /// Read values from stdin line by line (ex. 101) and apply it until EOF
+        #[arg(short, long)]
+        interactive: bool,
+
+        /// Show physical levels along with logical values
+        #[arg(short, long)]
+        physical: bool,
+
         /// GPIO chip path or name (ex. gpiochip0)
         #[arg(value_parser)]
         chip: std::path::PathBuf,
 
-        /// GPIO line-value pairs (ex. 0=1 11=0)
+        /// GPIO line-value pairs (ex. 0=1 11=0), values are optional in interactive mode
         #[arg(value_parser, required = true, num_args = ..=gpiod::MAX_VALUES)]
         line_values: Vec<LineValue>,
     },
@@ -107,6 +131,30 @@ pub enum Cmd {
         #[arg(short, long, value_enum, default_value = "both")]
         edge: gpiod::EdgeDetect,
 
+        /// Kernel event buffer size
+        #[arg(long, value_parser)]
+        buffer: Option<u32>,
+
+        /// Show event sequence numbers (request-wide/per-line, ABI v2 only)
+        #[arg(long)]
+        seqno: bool,
+
+        /// Clock to timestamp events (realtime is ABI v2 only)
+        #[arg(long, value_enum, default_value = "monotonic")]
+        clock: gpiod::EventClock,
+
+        /// Format of event timestamps (iso requires realtime clock)
+        #[arg(long, value_enum, default_value = "nanos")]
+        time_format: gpiod::TimeFormat,
+
+        /// Exit after given number of events
+        #[arg(short, long, value_parser)]
+        num_events: Option<usize>,
+
+        /// Do not print events, only the number of events at exit
+        #[arg(short, long)]
+        silent: bool,
+
         /// Consumer string
         #[arg(short, long, value_parser, default_value = "gpiomon")]
         consumer: String,
@@ -120,6 +168,21 @@ pub enum Cmd {
         lines: Vec<gpiod::LineId>,
     },
 
+    /// Watch info changes of GPIO lines (requested, released or reconfigured)
+    Watch {
+        /// Exit after given number of changes
+        #[arg(short, long, value_parser)]
+        num_events: Option<usize>,
+
+        /// GPIO chip path or name (ex. gpiochip0)
+        #[arg(value_parser)]
+        chip: std::path::PathBuf,
+
+        /// GPIO lines (ex. 0 11)
+        #[arg(value_parser, required = true)]
+        lines: Vec<gpiod::LineId>,
+    },
+
     #[cfg(feature = "complete")]
     /// Generate autocompletion
     Complete {
@@ -129,27 +192,41 @@ pub enum Cmd {
     },
 }
 
+/// Output format
+#[derive(Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// Human-readable text
+    Text,
+    /// JSON document per chip
+    Json,
+}
+
 #[derive(Clone)]
 pub struct LineValue {
     pub line: gpiod::LineId,
-    pub value: bool,
+    pub value: Option<bool>,
 }
 
 impl std::str::FromStr for LineValue {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> anyhow::Result<Self> {
-        let (k, v) = s
-            .split_once('=')
-            .ok_or_else(|| anyhow::anyhow!("Key-value pair expected (line=value)"))?;
+        let (k, v) = match s.split_once('=') {
+            Some((k, v)) => (k, Some(v)),
+            None => (s, None),
+        };
         let line = k
             .parse()
             .map_err(|_| anyhow::anyhow!("Invalid line offset"))?;
-        let value = match v.trim() {
-            "0" | "off" | "false" => false,
-            "1" | "on" | "true" => true,
-            _ => anyhow::bail!("Invalid line value"),
-        };
+        let value = v
+            .map(|v| {
+                Ok(match v.trim() {
+                    "0" | "off" | "false" => false,
+                    "1" | "on" | "true" => true,
+                    _ => anyhow::bail!("Invalid line value"),
+                })
+            })
+            .transpose()?;
         Ok(Self { line, value })
     }
 }
@@ -0,0 +1,38 @@
+use gpiod::{ChipInfo, LineId, LineInfo};
+use serde_json::{json, Value};
+
+/// Make JSON document with chip info
+///
+/// The lines are included when info of lines is given.
+pub fn chip_json(chip: &ChipInfo, lines: Option<&[LineInfo]>) -> Value {
+    let mut value = json!({
+        "name": chip.name(),
+        "label": chip.label(),
+        "num_lines": chip.num_lines(),
+    });
+
+    if let Some(lines) = lines {
+        value["lines"] = lines
+            .iter()
+            .enumerate()
+            .map(|(line, info)| line_json(line as _, info))
+            .collect();
+    }
+
+    value
+}
+
+/// Make JSON document with line info
+pub fn line_json(line: LineId, info: &LineInfo) -> Value {
+    json!({
+        "line": line,
+        "name": info.name,
+        "consumer": info.consumer,
+        "used": info.used,
+        "direction": info.direction.as_ref(),
+        "active": info.active.as_ref(),
+        "edge": info.edge.as_ref(),
+        "bias": info.bias.as_ref(),
+        "drive": info.drive.as_ref(),
+    })
+}
@@ -1,5 +1,7 @@
 #![doc = include_str!("../README.md")]
 
 mod args;
+mod json;
 
-pub use args::{Args, Cmd, LineValue};
+pub use args::{Args, Cmd, Format, LineValue};
+pub use json::{chip_json, line_json};
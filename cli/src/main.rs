@@ -1,51 +1,70 @@
-mod args;
-
 fn main() -> anyhow::Result<()> {
-    use args::{Args, Cmd};
+    use gpiod_cli::{chip_json, Args, Cmd, Format};
 
     let args: Args = clap::Parser::parse();
 
     match args.cmd {
-        Cmd::Detect => {
-            let chips = gpiod::Chip::list_devices()?
-                .into_iter()
-                .map(gpiod::Chip::new)
-                .collect::<std::io::Result<Vec<_>>>()?;
+        Cmd::Detect { format } => {
+            let (chips, failures) = gpiod::Chip::all_with_failures()?;
 
-            chips
-                .iter()
-                .rev() //Do it in reverse order because the numbers of the GPIO chips go from high to low
-                .for_each(|f| println!("{}", f));
+            for chip in &chips {
+                match format {
+                    Format::Text => println!("{}", chip),
+                    Format::Json => println!("{}", chip_json(chip, None)),
+                }
+            }
+
+            if !failures.is_empty() {
+                eprintln!("skipped {} inaccessible chip(s):", failures.len());
+                for (path, error) in &failures {
+                    eprintln!("\t{}: {}", path.display(), error);
+                }
+            }
         }
 
-        Cmd::Info { chip } => {
-            let chips = gpiod::Chip::list_devices()?
-                .into_iter()
-                .filter(|path| {
-                    chip.is_empty()
-                        || chip.iter().any(|chip| {
-                            path.to_str()
-                                .map(|path| path.ends_with(chip))
-                                .unwrap_or(false)
-                        })
-                })
-                .map(gpiod::Chip::new)
-                .collect::<std::io::Result<Vec<_>>>()?;
-
-            for index in (0..chips.len()).rev() {
-                let chip = &chips[index];
+        Cmd::Info { format, chip } => {
+            let (chips, failures) = if chip.is_empty() {
+                gpiod::Chip::all_with_failures()?
+            } else {
+                let paths = gpiod::Chip::list_devices()?.into_iter().filter(|path| {
+                    chip.iter().any(|chip| {
+                        path.to_str()
+                            .map(|path| path.ends_with(chip))
+                            .unwrap_or(false)
+                    })
+                });
+                let (mut chips, failures) = gpiod::Chip::open_devices(paths);
+                gpiod::sort_chips(&mut chips);
+                (chips, failures)
+            };
+
+            for chip in &chips {
+                if format == Format::Json {
+                    let lines = chip.line_info_all()?;
+                    println!("{}", chip_json(chip, Some(&lines)));
+                    continue;
+                }
                 println!("{}", chip);
-                for line in 0..chip.num_lines() {
-                    let line_info = chip.line_info(line)?;
+                for line_info in chip {
+                    let (line, line_info) = line_info?;
                     println!("\t line \t {}: \t {}", line, line_info);
                 }
             }
+
+            if !failures.is_empty() {
+                eprintln!("skipped {} inaccessible chip(s):", failures.len());
+                for (path, error) in &failures {
+                    eprintln!("\t{}: {}", path.display(), error);
+                }
+            }
         }
 
         Cmd::Get {
             bias,
             active,
             consumer,
+            labeled,
+            physical,
             chip,
             lines,
         } => {
@@ -62,10 +81,27 @@ fn main() -> anyhow::Result<()> {
 
             let values = input.get_values(values)?;
 
-            for value in values {
-                print!("{} ", if value { 1 } else { 0 });
+            if physical {
+                for (line, value) in lines.iter().zip(values) {
+                    println!(
+                        "line {}: logical={} physical={}",
+                        line,
+                        value as u8,
+                        active.physical(value) as u8
+                    );
+                }
+            } else if labeled {
+                let mut names = Vec::with_capacity(lines.len());
+                for line in &lines {
+                    names.push(chip.line_info(*line)?.name);
+                }
+                println!("{}", input.format_values(&values, &names));
+            } else {
+                for value in values {
+                    print!("{} ", if value { 1 } else { 0 });
+                }
+                println!();
             }
-            println!();
         }
 
         Cmd::Set {
@@ -73,6 +109,8 @@ fn main() -> anyhow::Result<()> {
             active,
             drive,
             consumer,
+            interactive,
+            physical,
             chip,
             line_values,
         } => {
@@ -83,6 +121,15 @@ fn main() -> anyhow::Result<()> {
                 .map(|pair| (pair.line, pair.value))
                 .unzip();
 
+            if !interactive && values.iter().any(Option::is_none) {
+                anyhow::bail!("Key-value pair expected (line=value)");
+            }
+
+            let values = values
+                .into_iter()
+                .map(|value| value.unwrap_or(false))
+                .collect::<Vec<_>>();
+
             let output = chip.request_lines(
                 gpiod::Options::output(&lines)
                     .active(active)
@@ -92,40 +139,123 @@ fn main() -> anyhow::Result<()> {
                     .consumer(&consumer),
             )?;
 
-            //output.set_values(values)?;
-            let values = output.get_values(values)?;
+            if interactive {
+                for line in std::io::stdin().lines() {
+                    let line = line?;
+                    let line = line.trim();
 
-            for value in values {
-                print!("{} ", if value { 1 } else { 0 });
+                    if line.is_empty() {
+                        continue;
+                    }
+
+                    output.set_values(gpiod::Values::from_line_order(line)?)?;
+                }
+            } else {
+                //output.set_values(values)?;
+                let values = output.get_values(values)?;
+
+                if physical {
+                    for (line, value) in lines.iter().zip(values) {
+                        println!(
+                            "line {}: logical={} physical={}",
+                            line,
+                            value as u8,
+                            active.physical(value) as u8
+                        );
+                    }
+                } else {
+                    for value in values {
+                        print!("{} ", if value { 1 } else { 0 });
+                    }
+                    println!();
+                }
             }
-            println!();
         }
 
         Cmd::Mon {
             edge,
             bias,
             active,
+            buffer,
+            seqno,
+            clock,
+            time_format,
+            num_events,
+            silent,
             consumer,
             chip,
             lines,
         } => {
             let chip = gpiod::Chip::new(&chip)?;
 
-            let input = chip.request_lines(
-                gpiod::Options::input(&lines)
-                    .active(active)
-                    .edge(edge)
-                    .bias(bias)
-                    .consumer(&consumer),
-            )?;
+            let mut options = gpiod::Options::input(&lines)
+                .active(active)
+                .edge(edge)
+                .bias(bias)
+                .event_clock(clock)
+                .consumer(&consumer);
 
-            for event in input {
-                let event = event?;
-                println!(
+            if let Some(size) = buffer {
+                options = options.event_buffer(size);
+            }
+
+            let mut input = chip.request_lines(options)?;
+
+            let mut last: Option<gpiod::Event> = None;
+            let mut count = 0;
+
+            while num_events.map(|num| count < num).unwrap_or(true) {
+                let event = input.read_event()?;
+                if let Some(last) = &last {
+                    let dropped = event.dropped_since(last);
+                    if dropped > 0 {
+                        eprintln!("warning: {} events dropped", dropped);
+                    }
+                }
+                last = Some(event);
+                count += 1;
+                if silent {
+                    continue;
+                }
+                print!(
                     "line {}: {}-edge [{}]",
                     lines[event.line as usize],
                     event.edge,
-                    event.time.as_nanos(),
+                    event.format_time_as(time_format),
+                );
+                if seqno {
+                    if let (Some(seqno), Some(line_seqno)) = (event.seqno, event.line_seqno) {
+                        print!(" seq {}/{}", seqno, line_seqno);
+                    }
+                }
+                println!();
+            }
+
+            println!("{} events", count);
+        }
+
+        Cmd::Watch {
+            num_events,
+            chip,
+            lines,
+        } => {
+            let chip = gpiod::Chip::new(&chip)?;
+
+            for line in &lines {
+                chip.watch_line_info(*line)?;
+            }
+
+            let mut count = 0;
+
+            while num_events.map(|num| count < num).unwrap_or(true) {
+                let change = chip.read_line_info_change()?;
+                count += 1;
+                println!(
+                    "line \t {}: \t {} [{}] {}",
+                    change.line,
+                    change.kind,
+                    change.time.as_nanos(),
+                    change.info,
                 );
             }
         }
@@ -1,61 +1,74 @@
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    use gpiod_cli::{Args, Cmd};
+    use gpiod_cli::{chip_json, Args, Cmd, Format};
     use tokio_gpiod as gpiod;
 
     let args: Args = clap::Parser::parse();
 
     match args.cmd {
-        Cmd::Detect => {
-            let chips = futures::future::join_all(
-                gpiod::Chip::list_devices()
-                    .await?
-                    .into_iter()
-                    .map(gpiod::Chip::new),
-            )
-            .await
-            .into_iter()
-            .collect::<std::io::Result<Vec<_>>>()?;
-
-            chips
-                .iter()
-                .rev() //Do it in reverse order because the numbers of the GPIO chips go from high to low
-                .for_each(|f| println!("{}", f));
+        Cmd::Detect { format } => {
+            let (chips, failures) = gpiod::Chip::all_with_failures().await?;
+
+            for chip in &chips {
+                match format {
+                    Format::Text => println!("{}", chip),
+                    Format::Json => println!("{}", chip_json(chip, None)),
+                }
+            }
+
+            if !failures.is_empty() {
+                eprintln!("skipped {} inaccessible chip(s):", failures.len());
+                for (path, error) in &failures {
+                    eprintln!("\t{}: {}", path.display(), error);
+                }
+            }
         }
 
-        Cmd::Info { chip } => {
-            let chips = futures::future::join_all(
-                gpiod::Chip::list_devices()
+        Cmd::Info { format, chip } => {
+            let (chips, failures) = if chip.is_empty() {
+                gpiod::Chip::all_with_failures().await?
+            } else {
+                let paths = gpiod::Chip::list_devices()
                     .await?
                     .into_iter()
                     .filter(|path| {
-                        chip.is_empty()
-                            || chip.iter().any(|chip| {
-                                path.to_str()
-                                    .map(|path| path.ends_with(chip))
-                                    .unwrap_or(false)
-                            })
-                    })
-                    .map(gpiod::Chip::new),
-            )
-            .await
-            .into_iter()
-            .collect::<std::io::Result<Vec<_>>>()?;
-
-            for index in (0..chips.len()).rev() {
-                let chip = &chips[index];
+                        chip.iter().any(|chip| {
+                            path.to_str()
+                                .map(|path| path.ends_with(chip))
+                                .unwrap_or(false)
+                        })
+                    });
+                let (mut chips, failures) = gpiod::Chip::open_devices(paths).await;
+                gpiod::sort_chips(&mut chips);
+                (chips, failures)
+            };
+
+            for chip in &chips {
+                let lines = chip.line_info_all().await?;
+                if format == Format::Json {
+                    println!("{}", chip_json(chip, Some(&lines)));
+                    continue;
+                }
                 println!("{}", chip);
-                for line in 0..chip.num_lines() {
-                    let line_info = chip.line_info(line).await?;
+                for (line, line_info) in lines.into_iter().enumerate() {
                     println!("\t line \t {}: \t {}", line, line_info);
                 }
             }
+
+            if !failures.is_empty() {
+                eprintln!("skipped {} inaccessible chip(s):", failures.len());
+                for (path, error) in &failures {
+                    eprintln!("\t{}: {}", path.display(), error);
+                }
+            }
         }
 
         Cmd::Get {
             bias,
             active,
             consumer,
+            labeled,
+            physical,
             chip,
             lines,
         } => {
@@ -74,10 +87,27 @@ async fn main() -> anyhow::Result<()> {
 
             let values = input.get_values(values).await?;
 
-            for value in values {
-                print!("{} ", if value { 1 } else { 0 });
+            if physical {
+                for (line, value) in lines.iter().zip(values) {
+                    println!(
+                        "line {}: logical={} physical={}",
+                        line,
+                        value as u8,
+                        active.physical(value) as u8
+                    );
+                }
+            } else if labeled {
+                let mut names = Vec::with_capacity(lines.len());
+                for line in &lines {
+                    names.push(chip.line_info(*line).await?.name);
+                }
+                println!("{}", input.format_values(&values, &names));
+            } else {
+                for value in values {
+                    print!("{} ", if value { 1 } else { 0 });
+                }
+                println!();
             }
-            println!();
         }
 
         Cmd::Set {
@@ -85,6 +115,8 @@ async fn main() -> anyhow::Result<()> {
             active,
             drive,
             consumer,
+            interactive,
+            physical,
             chip,
             line_values,
         } => {
@@ -95,6 +127,15 @@ async fn main() -> anyhow::Result<()> {
                 .map(|pair| (pair.line, pair.value))
                 .unzip();
 
+            if !interactive && values.iter().any(Option::is_none) {
+                anyhow::bail!("Key-value pair expected (line=value)");
+            }
+
+            let values = values
+                .into_iter()
+                .map(|value| value.unwrap_or(false))
+                .collect::<Vec<_>>();
+
             let output = chip
                 .request_lines(
                     gpiod::Options::output(&lines)
@@ -106,42 +147,131 @@ async fn main() -> anyhow::Result<()> {
                 )
                 .await?;
 
-            //output.set_values(values)?;
-            let values = output.get_values(values).await?;
+            if interactive {
+                use tokio::io::AsyncBufReadExt;
+
+                let mut stdin = tokio::io::BufReader::new(tokio::io::stdin()).lines();
 
-            for value in values {
-                print!("{} ", if value { 1 } else { 0 });
+                while let Some(line) = stdin.next_line().await? {
+                    let line = line.trim();
+
+                    if line.is_empty() {
+                        continue;
+                    }
+
+                    output
+                        .set_values(gpiod::Values::from_line_order(line)?)
+                        .await?;
+                }
+            } else {
+                //output.set_values(values)?;
+                let values = output.get_values(values).await?;
+
+                if physical {
+                    for (line, value) in lines.iter().zip(values) {
+                        println!(
+                            "line {}: logical={} physical={}",
+                            line,
+                            value as u8,
+                            active.physical(value) as u8
+                        );
+                    }
+                } else {
+                    for value in values {
+                        print!("{} ", if value { 1 } else { 0 });
+                    }
+                    println!();
+                }
             }
-            println!();
         }
 
         Cmd::Mon {
             edge,
             bias,
             active,
+            buffer,
+            seqno,
+            clock,
+            time_format,
+            num_events,
+            silent,
             consumer,
             chip,
             lines,
         } => {
             let chip = gpiod::Chip::new(&chip).await?;
 
-            let mut input = chip
-                .request_lines(
-                    gpiod::Options::input(&lines)
-                        .active(active)
-                        .edge(edge)
-                        .bias(bias)
-                        .consumer(&consumer),
-                )
-                .await?;
+            let mut options = gpiod::Options::input(&lines)
+                .active(active)
+                .edge(edge)
+                .bias(bias)
+                .event_clock(clock)
+                .consumer(&consumer);
+
+            if let Some(size) = buffer {
+                options = options.event_buffer(size);
+            }
 
-            loop {
+            let mut input = chip.request_lines(options).await?;
+
+            let mut last: Option<gpiod::Event> = None;
+            let mut count = 0;
+
+            while num_events.map(|num| count < num).unwrap_or(true) {
                 let event = input.read_event().await?;
-                println!(
+                if let Some(last) = &last {
+                    let dropped = event.dropped_since(last);
+                    if dropped > 0 {
+                        eprintln!("warning: {} events dropped", dropped);
+                    }
+                }
+                last = Some(event);
+                count += 1;
+                if silent {
+                    continue;
+                }
+                print!(
                     "line {}: {}-edge [{}]",
                     lines[event.line as usize],
                     event.edge,
-                    event.time.as_nanos(),
+                    event.format_time_as(time_format),
+                );
+                if seqno {
+                    if let (Some(seqno), Some(line_seqno)) = (event.seqno, event.line_seqno) {
+                        print!(" seq {}/{}", seqno, line_seqno);
+                    }
+                }
+                println!();
+            }
+
+            println!("{} events", count);
+        }
+
+        Cmd::Watch {
+            num_events,
+            chip,
+            lines,
+        } => {
+            use futures_util::StreamExt;
+
+            let chip = gpiod::Chip::new(&chip).await?;
+
+            for line in &lines {
+                chip.watch_line_info(*line).await?;
+            }
+
+            let mut changes = std::pin::pin!(chip
+                .line_info_changes()
+                .take(num_events.unwrap_or(usize::MAX)));
+
+            while let Some(change) = changes.next().await {
+                let change = change?;
+                println!(
+                    "line \t {}: \t {} [{}] {}",
+                    change.line,
+                    change.kind,
+                    change.time.as_nanos(),
+                    change.info,
                 );
             }
         }
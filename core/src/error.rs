@@ -0,0 +1,138 @@
+use std::{fmt, io};
+
+/// Result of GPIO operations
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Error of GPIO operations
+///
+/// The errors which commonly needs special handling (like waiting out a busy line) are
+/// recognized by errno and has own variants. Use [Error::kind] to check it as IO error kind.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Line is already requested by another consumer (EBUSY)
+    LineBusy(io::Error),
+    /// Not enough permissions to access the chip (EACCES or EPERM)
+    PermissionDenied(io::Error),
+    /// Line with given name or offset not found
+    LineNotFound(String),
+    /// Too many lines given for single request
+    TooManyLines,
+    /// Other IO error
+    Io(io::Error),
+}
+
+impl Error {
+    /// Get IO error kind
+    pub fn kind(&self) -> io::ErrorKind {
+        match self {
+            Self::LineBusy(_) => io::ErrorKind::ResourceBusy,
+            Self::PermissionDenied(_) => io::ErrorKind::PermissionDenied,
+            Self::LineNotFound(_) | Self::TooManyLines => io::ErrorKind::InvalidInput,
+            Self::Io(error) => error.kind(),
+        }
+    }
+
+    /// Get OS error code when error comes from OS
+    pub fn raw_os_error(&self) -> Option<i32> {
+        match self {
+            Self::LineBusy(error) | Self::PermissionDenied(error) | Self::Io(error) => {
+                error.raw_os_error()
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LineBusy(error) => write!(f, "Line busy: {}", error),
+            Self::PermissionDenied(error) => write!(f, "Permission denied: {}", error),
+            Self::LineNotFound(line) => write!(f, "Line not found: {}", line),
+            Self::TooManyLines => "Too many lines".fmt(f),
+            Self::Io(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::LineBusy(error) | Self::PermissionDenied(error) | Self::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        match error.raw_os_error() {
+            Some(errno::EBUSY) => Self::LineBusy(error),
+            Some(errno::EACCES | errno::EPERM) => Self::PermissionDenied(error),
+            _ => Self::Io(error),
+        }
+    }
+}
+
+impl From<io::ErrorKind> for Error {
+    fn from(kind: io::ErrorKind) -> Self {
+        Self::Io(kind.into())
+    }
+}
+
+impl From<nix::Error> for Error {
+    fn from(error: nix::Error) -> Self {
+        io::Error::from(error).into()
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::LineBusy(error) | Error::PermissionDenied(error) | Error::Io(error) => error,
+            error => io::Error::new(error.kind(), error.to_string()),
+        }
+    }
+}
+
+mod errno {
+    use nix::errno::Errno;
+
+    pub const EBUSY: i32 = Errno::EBUSY as _;
+    pub const EACCES: i32 = Errno::EACCES as _;
+    pub const EPERM: i32 = Errno::EPERM as _;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::error::Error as _;
+
+    #[test]
+    fn from_os_error() {
+        let error = Error::from(io::Error::from_raw_os_error(errno::EBUSY));
+        assert!(matches!(error, Error::LineBusy(_)));
+        assert_eq!(error.kind(), io::ErrorKind::ResourceBusy);
+        assert!(error.source().is_some());
+
+        let error = Error::from(io::Error::from_raw_os_error(errno::EACCES));
+        assert!(matches!(error, Error::PermissionDenied(_)));
+
+        let error = Error::from(nix::Error::EPERM);
+        assert!(matches!(error, Error::PermissionDenied(_)));
+
+        let error = Error::from(io::ErrorKind::WouldBlock);
+        assert!(matches!(error, Error::Io(_)));
+        assert_eq!(error.kind(), io::ErrorKind::WouldBlock);
+    }
+
+    #[test]
+    fn into_io_error() {
+        let error = io::Error::from(Error::from(io::Error::from_raw_os_error(errno::EBUSY)));
+        assert_eq!(error.raw_os_error(), Some(errno::EBUSY));
+
+        let error = io::Error::from(Error::TooManyLines);
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+}
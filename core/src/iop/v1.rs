@@ -1,11 +1,14 @@
 use crate::{
     raw::v1::*, utils::*, Active, AsValues, AsValuesMut, Bias, BitId, Direction, Drive, Edge,
-    EdgeDetect, Event, LineId, LineInfo, Result,
+    EdgeDetect, Event, EventClock, LineChange, LineChangeKind, LineId, LineInfo, Result, Values,
 };
 
 /// Raw event to read from fd
 pub type RawEvent = GpioEventData;
 
+/// Raw line info change to read from chip fd
+pub type RawInfoChange = GpioLineInfoChanged;
+
 impl GpioLineInfo {
     pub fn as_info(&self) -> Result<LineInfo> {
         let direction = if is_set(self.flags, GPIOLINE_FLAG_IS_OUT) {
@@ -51,12 +54,34 @@ impl GpioLineInfo {
             used,
             bias,
             drive,
+            debounce: None,
+            attributes: Vec::new(),
             name,
             consumer,
         })
     }
 }
 
+impl GpioLineInfoChanged {
+    pub fn as_info_change(&self) -> Result<LineChange> {
+        let kind = match self.event_type {
+            GPIOLINE_CHANGED_REQUESTED => LineChangeKind::Requested,
+            GPIOLINE_CHANGED_RELEASED => LineChangeKind::Released,
+            GPIOLINE_CHANGED_CONFIG => LineChangeKind::Reconfigured,
+            _ => return Err(invalid_data("Unknown line change")),
+        };
+
+        let time = time_from_nanos(self.timestamp);
+
+        Ok(LineChange {
+            line: self.info.line_offset,
+            kind,
+            time,
+            info: self.info.as_info()?,
+        })
+    }
+}
+
 impl GpioHandleRequest {
     pub fn new(
         lines: &[LineId],
@@ -64,6 +89,7 @@ impl GpioHandleRequest {
         active: Active,
         bias: Option<Bias>,
         drive: Option<Drive>,
+        values: Option<Values>,
         consumer: &str,
     ) -> Result<Self> {
         let mut request = GpioHandleRequest::default();
@@ -74,43 +100,106 @@ impl GpioHandleRequest {
 
         request.line_offsets[..lines.len()].copy_from_slice(lines);
 
-        request.flags |= match direction {
-            Direction::Input => GPIOHANDLE_REQUEST_INPUT,
-            // Mixing input and output flags is not allowed
-            // see https://github.com/torvalds/linux/blob/v5.18/drivers/gpio/gpiolib-cdev.c#L92-L98
-            Direction::Output => GPIOHANDLE_REQUEST_OUTPUT,
-        };
+        request.flags = handle_flags(direction, active, bias, drive);
 
-        if matches!(active, Active::Low) {
-            request.flags |= GPIOHANDLE_REQUEST_ACTIVE_LOW;
+        // Initial values is applied by kernel atomically with request
+        if let (Direction::Output, Some(values)) = (direction, values) {
+            request.default_values = GpioHandleData::from_values(lines.len(), values).values;
         }
 
-        if let Some(bias) = bias {
-            request.flags |= match bias {
-                Bias::PullUp => GPIOHANDLE_REQUEST_BIAS_PULL_UP,
-                Bias::PullDown => GPIOHANDLE_REQUEST_BIAS_PULL_DOWN,
-                Bias::Disable => GPIOHANDLE_REQUEST_BIAS_DISABLE,
-            };
-        }
+        safe_set_str(&mut request.consumer_label, consumer)?;
 
-        if matches!(direction, Direction::Output) {
-            // Set drive flags is valid only for output
-            // see https://github.com/torvalds/linux/blob/v5.18/drivers/gpio/gpiolib-cdev.c#L109-L113
-            if let Some(drive) = drive {
-                match drive {
-                    Drive::OpenDrain => request.flags |= GPIOHANDLE_REQUEST_OPEN_DRAIN,
-                    Drive::OpenSource => request.flags |= GPIOHANDLE_REQUEST_OPEN_SOURCE,
-                    _ => (),
-                }
-            }
+        Ok(request)
+    }
+}
+
+impl GpioHandleConfig {
+    pub fn new(
+        len: usize,
+        direction: Direction,
+        active: Active,
+        bias: Option<Bias>,
+        drive: Option<Drive>,
+        values: Option<Values>,
+    ) -> Self {
+        let mut config = GpioHandleConfig::default();
+
+        config.flags = handle_flags(direction, active, bias, drive);
+
+        if let Some(values) = values {
+            config.default_values = GpioHandleData::from_values(len, values).values;
         }
 
+        config
+    }
+}
+
+impl GpioEventRequest {
+    pub fn new(
+        line: LineId,
+        active: Active,
+        edge: EdgeDetect,
+        bias: Option<Bias>,
+        consumer: &str,
+    ) -> Result<Self> {
+        let mut request = GpioEventRequest {
+            lineoffset: line,
+            handleflags: handle_flags(Direction::Input, active, bias, None),
+            eventflags: match edge {
+                EdgeDetect::Disable => 0,
+                EdgeDetect::Rising => GPIOEVENT_REQUEST_RISING_EDGE,
+                EdgeDetect::Falling => GPIOEVENT_REQUEST_FALLING_EDGE,
+                EdgeDetect::Both => GPIOEVENT_REQUEST_BOTH_EDGES,
+            },
+            ..Default::default()
+        };
+
         safe_set_str(&mut request.consumer_label, consumer)?;
 
         Ok(request)
     }
 }
 
+pub fn handle_flags(
+    direction: Direction,
+    active: Active,
+    bias: Option<Bias>,
+    drive: Option<Drive>,
+) -> u32 {
+    let mut flags = match direction {
+        Direction::Input => GPIOHANDLE_REQUEST_INPUT,
+        // Mixing input and output flags is not allowed
+        // see https://github.com/torvalds/linux/blob/v5.18/drivers/gpio/gpiolib-cdev.c#L92-L98
+        Direction::Output => GPIOHANDLE_REQUEST_OUTPUT,
+    };
+
+    if matches!(active, Active::Low) {
+        flags |= GPIOHANDLE_REQUEST_ACTIVE_LOW;
+    }
+
+    if let Some(bias) = bias {
+        flags |= match bias {
+            Bias::PullUp => GPIOHANDLE_REQUEST_BIAS_PULL_UP,
+            Bias::PullDown => GPIOHANDLE_REQUEST_BIAS_PULL_DOWN,
+            Bias::Disable => GPIOHANDLE_REQUEST_BIAS_DISABLE,
+        };
+    }
+
+    if matches!(direction, Direction::Output) {
+        // Set drive flags is valid only for output
+        // see https://github.com/torvalds/linux/blob/v5.18/drivers/gpio/gpiolib-cdev.c#L109-L113
+        if let Some(drive) = drive {
+            match drive {
+                Drive::OpenDrain => flags |= GPIOHANDLE_REQUEST_OPEN_DRAIN,
+                Drive::OpenSource => flags |= GPIOHANDLE_REQUEST_OPEN_SOURCE,
+                _ => (),
+            }
+        }
+    }
+
+    flags
+}
+
 impl GpioHandleData {
     pub fn fill_values(&self, len: usize, values: &mut impl AsValuesMut) {
         for id in 0..len {
@@ -134,7 +223,7 @@ impl GpioHandleData {
 }
 
 impl GpioEventData {
-    pub fn as_event(&self, line: BitId) -> Result<Event> {
+    pub fn as_event(&self, line: BitId, offset: LineId) -> Result<Event> {
         let edge = match self.id {
             GPIOEVENT_EVENT_RISING_EDGE => Edge::Rising,
             GPIOEVENT_EVENT_FALLING_EDGE => Edge::Falling,
@@ -143,6 +232,51 @@ impl GpioEventData {
 
         let time = time_from_nanos(self.timestamp);
 
-        Ok(Event { line, edge, time })
+        Ok(Event {
+            line,
+            offset,
+            edge,
+            time,
+            clock: EventClock::Monotonic,
+            seqno: None,
+            line_seqno: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn handle_request_default_values() {
+        let values = Some([true, false, true].convert());
+
+        let request = GpioHandleRequest::new(
+            &[3, 5, 7],
+            Direction::Output,
+            Active::High,
+            None,
+            None,
+            values,
+            "gpiod",
+        )
+        .unwrap();
+
+        assert_eq!(request.flags, GPIOHANDLE_REQUEST_OUTPUT);
+        assert_eq!(request.default_values[..4], [1, 0, 1, 0]);
+
+        let request = GpioHandleRequest::new(
+            &[3, 5, 7],
+            Direction::Input,
+            Active::High,
+            None,
+            None,
+            values,
+            "gpiod",
+        )
+        .unwrap();
+
+        assert_eq!(request.default_values[..3], [0, 0, 0]);
     }
 }
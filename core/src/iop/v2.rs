@@ -1,11 +1,15 @@
 use crate::{
-    raw::v2::*, utils::*, Active, AsValuesMut, Bias, Direction, Drive, Edge, EdgeDetect, Event,
-    LineId, LineInfo, LineMap, Result, Values,
+    raw::v2::*, utils::*, Active, AsValuesMut, Bias, Direction, Drive, Edge, EdgeDetect, Error,
+    Event, EventClock, LineAttribute, LineChange, LineChangeKind, LineId, LineInfo, LineMap,
+    LineOverride, Result, Time, Values,
 };
 
 /// Raw event ro read from fd
 pub type RawEvent = GpioLineEvent;
 
+/// Raw line info change to read from chip fd
+pub type RawInfoChange = GpioLineInfoChanged;
+
 impl GpioLineInfo {
     pub fn as_info(&self) -> Result<LineInfo> {
         let direction = if is_set(self.flags, GPIO_LINE_FLAG_OUTPUT) {
@@ -49,6 +53,14 @@ impl GpioLineInfo {
             (false, true) => Drive::OpenSource,
             _ => Drive::PushPull,
         };
+        let attributes = self.attrs[..(self.num_attrs as usize).min(GPIO_LINE_NUM_ATTRS_MAX)]
+            .iter()
+            .map(GpioLineAttr::as_attribute)
+            .collect::<Vec<_>>();
+        let debounce = attributes.iter().find_map(|attr| match attr {
+            LineAttribute::Debounce(period) => Some(*period),
+            _ => None,
+        });
         let name = safe_get_str(&self.name)?.into();
         let consumer = safe_get_str(&self.consumer)?.into();
 
@@ -59,12 +71,53 @@ impl GpioLineInfo {
             used,
             bias,
             drive,
+            debounce,
+            attributes,
             name,
             consumer,
         })
     }
 }
 
+impl GpioLineInfoChanged {
+    pub fn as_info_change(&self) -> Result<LineChange> {
+        let kind = match self.event_type {
+            GPIO_LINE_CHANGED_REQUESTED => LineChangeKind::Requested,
+            GPIO_LINE_CHANGED_RELEASED => LineChangeKind::Released,
+            GPIO_LINE_CHANGED_CONFIG => LineChangeKind::Reconfigured,
+            _ => return Err(invalid_data("Unknown line change")),
+        };
+
+        let time = time_from_nanos(self.timestamp_ns);
+
+        Ok(LineChange {
+            line: self.info.offset,
+            kind,
+            time,
+            info: self.info.as_info()?,
+        })
+    }
+}
+
+impl GpioLineAttr {
+    pub fn as_attribute(&self) -> LineAttribute {
+        // it's safe because union fields is plain integers
+        unsafe {
+            match self.id {
+                GPIO_LINE_ATTR_ID_FLAGS => LineAttribute::Flags(self.val.flags),
+                GPIO_LINE_ATTR_ID_OUTPUT_VALUES => LineAttribute::OutputValues(self.val.values),
+                GPIO_LINE_ATTR_ID_DEBOUNCE => {
+                    LineAttribute::Debounce(Time::from_micros(self.val.debounce_period_us as _))
+                }
+                id => LineAttribute::Unknown {
+                    id,
+                    value: self.val.values,
+                },
+            }
+        }
+    }
+}
+
 impl AsMut<GpioLineValues> for Values {
     fn as_mut(&mut self) -> &mut GpioLineValues {
         // it's safe because memory layout is same
@@ -72,6 +125,104 @@ impl AsMut<GpioLineValues> for Values {
     }
 }
 
+impl GpioLineConfig {
+    fn add_attr(&mut self, attr: GpioLineAttr, mask: u64) -> Result<()> {
+        let index = self.num_attrs as usize;
+
+        if index >= GPIO_LINE_NUM_ATTRS_MAX {
+            return Err(Error::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "Too many distinct line configurations (max {} attributes)",
+                    GPIO_LINE_NUM_ATTRS_MAX
+                ),
+            )));
+        }
+
+        self.attrs[index] = GpioLineConfigAttr { attr, mask };
+        self.num_attrs += 1;
+
+        Ok(())
+    }
+
+    fn add_line_flags(&mut self, id: usize, flags: u64) -> Result<()> {
+        let attrs = &mut self.attrs[..self.num_attrs as usize];
+
+        // lines with same flags shares single attribute
+        if let Some(attr) = attrs.iter_mut().find(|attr| {
+            attr.attr.id == GPIO_LINE_ATTR_ID_FLAGS && unsafe { attr.attr.val.flags } == flags
+        }) {
+            attr.mask |= 1 << id;
+            return Ok(());
+        }
+
+        let mut attr = GpioLineAttr::default();
+        attr.id = GPIO_LINE_ATTR_ID_FLAGS;
+        attr.val.flags = flags;
+
+        self.add_attr(attr, 1 << id)
+    }
+}
+
+fn line_flags(
+    direction: Direction,
+    active: Active,
+    edge: Option<EdgeDetect>,
+    bias: Option<Bias>,
+    drive: Option<Drive>,
+    clock: EventClock,
+) -> u64 {
+    let mut flags = match direction {
+        Direction::Input => GPIO_LINE_FLAG_INPUT,
+        // Mixing input and output flags is not allowed
+        // see https://github.com/torvalds/linux/blob/v5.18/drivers/gpio/gpiolib-cdev.c#L895-L901
+        Direction::Output => GPIO_LINE_FLAG_OUTPUT,
+    };
+
+    if matches!(active, Active::Low) {
+        flags |= GPIO_LINE_FLAG_ACTIVE_LOW;
+    }
+
+    if matches!(direction, Direction::Input) {
+        // Set edge flags is valid only for input
+        // see https://github.com/torvalds/linux/blob/v5.18/drivers/gpio/gpiolib-cdev.c#L903-L906
+        if let Some(edge) = edge {
+            match edge {
+                EdgeDetect::Rising => flags |= GPIO_LINE_FLAG_EDGE_RISING,
+                EdgeDetect::Falling => flags |= GPIO_LINE_FLAG_EDGE_FALLING,
+                EdgeDetect::Both => flags |= GPIO_LINE_FLAG_EDGE_BOTH,
+                _ => {}
+            }
+        }
+
+        if matches!(clock, EventClock::Realtime) {
+            flags |= GPIO_LINE_FLAG_EVENT_CLOCK_REALTIME;
+        }
+    }
+
+    if let Some(bias) = bias {
+        flags |= match bias {
+            Bias::PullUp => GPIO_LINE_FLAG_BIAS_PULL_UP,
+            Bias::PullDown => GPIO_LINE_FLAG_BIAS_PULL_DOWN,
+            Bias::Disable => GPIO_LINE_FLAG_BIAS_DISABLED,
+        }
+    }
+
+    if matches!(direction, Direction::Output) {
+        // Set drive flags is valid only for output
+        // see https://github.com/torvalds/linux/blob/v5.18/drivers/gpio/gpiolib-cdev.c#L917-L920
+        if let Some(drive) = drive {
+            match drive {
+                Drive::OpenDrain => flags |= GPIO_LINE_FLAG_OPEN_DRAIN,
+                Drive::OpenSource => flags |= GPIO_LINE_FLAG_OPEN_SOURCE,
+                _ => (),
+            }
+        }
+    }
+
+    flags
+}
+
 impl GpioLineRequest {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
@@ -81,7 +232,10 @@ impl GpioLineRequest {
         edge: Option<EdgeDetect>,
         bias: Option<Bias>,
         drive: Option<Drive>,
+        debounce: Option<Time>,
+        clock: EventClock,
         values: Option<Values>,
+        overrides: &[LineOverride],
         consumer: &str,
     ) -> Result<Self> {
         let mut request = GpioLineRequest::default();
@@ -92,70 +246,88 @@ impl GpioLineRequest {
 
         request.offsets[..lines.len()].copy_from_slice(lines);
 
-        let config = &mut request.config;
+        request.config = GpioLineConfig::new(
+            lines, direction, active, edge, bias, drive, debounce, clock, values, overrides,
+        )?;
 
-        config.flags |= match direction {
-            Direction::Input => GPIO_LINE_FLAG_INPUT,
-            // Mixing input and output flags is not allowed
-            // see https://github.com/torvalds/linux/blob/v5.18/drivers/gpio/gpiolib-cdev.c#L895-L901
-            Direction::Output => GPIO_LINE_FLAG_OUTPUT,
-        };
+        safe_set_str(&mut request.consumer, consumer)?;
 
-        if matches!(active, Active::Low) {
-            config.flags |= GPIO_LINE_FLAG_ACTIVE_LOW;
-        }
+        Ok(request)
+    }
+}
 
-        if matches!(direction, Direction::Input) {
-            // Set edge flags is valid only for input
-            // see https://github.com/torvalds/linux/blob/v5.18/drivers/gpio/gpiolib-cdev.c#L903-L906
-            if let Some(edge) = edge {
-                match edge {
-                    EdgeDetect::Rising => config.flags |= GPIO_LINE_FLAG_EDGE_RISING,
-                    EdgeDetect::Falling => config.flags |= GPIO_LINE_FLAG_EDGE_FALLING,
-                    EdgeDetect::Both => config.flags |= GPIO_LINE_FLAG_EDGE_BOTH,
-                    _ => {}
-                }
-            }
-        }
+impl GpioLineConfig {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        lines: &[LineId],
+        direction: Direction,
+        active: Active,
+        edge: Option<EdgeDetect>,
+        bias: Option<Bias>,
+        drive: Option<Drive>,
+        debounce: Option<Time>,
+        clock: EventClock,
+        values: Option<Values>,
+        overrides: &[LineOverride],
+    ) -> Result<Self> {
+        let mut config = GpioLineConfig::default();
 
-        if let Some(bias) = bias {
-            config.flags |= match bias {
-                Bias::PullUp => GPIO_LINE_FLAG_BIAS_PULL_UP,
-                Bias::PullDown => GPIO_LINE_FLAG_BIAS_PULL_DOWN,
-                Bias::Disable => GPIO_LINE_FLAG_BIAS_DISABLED,
-            }
+        config.flags = line_flags(direction, active, edge, bias, drive, clock);
+
+        if overrides.iter().any(|item| !lines.contains(&item.line)) {
+            return Err(invalid_input("Unknown line offset"));
         }
 
-        if matches!(direction, Direction::Output) {
-            // Set drive flags is valid only for output
-            // see https://github.com/torvalds/linux/blob/v5.18/drivers/gpio/gpiolib-cdev.c#L917-L920
-            if let Some(drive) = drive {
-                match drive {
-                    Drive::OpenDrain => config.flags |= GPIO_LINE_FLAG_OPEN_DRAIN,
-                    Drive::OpenSource => config.flags |= GPIO_LINE_FLAG_OPEN_SOURCE,
-                    _ => (),
+        for (id, line) in lines.iter().enumerate() {
+            if let Some(item) = overrides.iter().find(|item| item.line == *line) {
+                let flags = line_flags(
+                    direction,
+                    item.active.unwrap_or(active),
+                    item.edge.or(edge),
+                    item.bias.or(bias),
+                    item.drive.or(drive),
+                    clock,
+                );
+
+                if flags != config.flags {
+                    config.add_line_flags(id, flags)?;
                 }
             }
+        }
 
+        if matches!(direction, Direction::Output) {
             if let Some(mut values) = values {
                 values.truncate(lines.len() as _);
 
-                config.num_attrs = 1;
-                let attr = &mut config.attrs[0];
-                attr.attr.id = GPIO_LINE_ATTR_ID_OUTPUT_VALUES;
-                attr.mask = values.mask;
-                attr.attr.val.values = values.bits;
+                let mut attr = GpioLineAttr::default();
+                attr.id = GPIO_LINE_ATTR_ID_OUTPUT_VALUES;
+                attr.val.values = values.bits;
+
+                config.add_attr(attr, values.mask)?;
             }
         }
 
-        safe_set_str(&mut request.consumer, consumer)?;
+        if matches!(direction, Direction::Input) {
+            if let Some(debounce) = debounce {
+                let period = u32::try_from(debounce.as_micros())
+                    .map_err(|_| invalid_input("Debounce period too long"))?;
 
-        Ok(request)
+                let mut attr = GpioLineAttr::default();
+                attr.id = GPIO_LINE_ATTR_ID_DEBOUNCE;
+                attr.val.debounce_period_us = period;
+
+                let mask = u64::MAX.checked_shr(64 - lines.len() as u32).unwrap_or(0);
+
+                config.add_attr(attr, mask)?;
+            }
+        }
+
+        Ok(config)
     }
 }
 
 impl GpioLineEvent {
-    pub fn as_event(&self, line_map: &LineMap) -> Result<Event> {
+    pub fn as_event(&self, line_map: &LineMap, clock: EventClock) -> Result<Event> {
         let line = line_map.get(self.offset)?;
 
         let edge = match self.id {
@@ -166,6 +338,307 @@ impl GpioLineEvent {
 
         let time = time_from_nanos(self.timestamp_ns);
 
-        Ok(Event { line, edge, time })
+        Ok(Event {
+            line,
+            offset: self.offset,
+            edge,
+            time,
+            clock,
+            seqno: Some(self.seqno),
+            line_seqno: Some(self.line_seqno),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AsValues, LineConfig, Masked, Options};
+
+    fn request(
+        options: Options<crate::Input, impl AsRef<[LineId]>, &str>,
+    ) -> Result<GpioLineRequest> {
+        GpioLineRequest::new(
+            options.lines.as_ref(),
+            Direction::Input,
+            options.active,
+            options.edge,
+            options.bias,
+            options.drive,
+            options.debounce,
+            options.event_clock,
+            options.values,
+            &options.overrides,
+            options.consumer,
+        )
+    }
+
+    #[test]
+    fn line_flags_overrides() {
+        let request = request(
+            Options::input([0, 1, 2, 3, 4, 5, 6, 7])
+                .line_bias([0, 1, 2, 3], Bias::PullUp)
+                .line_bias([4, 5, 6, 7], Bias::PullDown),
+        )
+        .unwrap();
+
+        let config = &request.config;
+
+        assert_eq!(config.flags, GPIO_LINE_FLAG_INPUT);
+        assert_eq!(config.num_attrs, 2);
+
+        assert_eq!(config.attrs[0].attr.id, GPIO_LINE_ATTR_ID_FLAGS);
+        assert_eq!(config.attrs[0].mask, 0b00001111);
+        assert_eq!(
+            unsafe { config.attrs[0].attr.val.flags },
+            GPIO_LINE_FLAG_INPUT | GPIO_LINE_FLAG_BIAS_PULL_UP
+        );
+
+        assert_eq!(config.attrs[1].attr.id, GPIO_LINE_ATTR_ID_FLAGS);
+        assert_eq!(config.attrs[1].mask, 0b11110000);
+        assert_eq!(
+            unsafe { config.attrs[1].attr.val.flags },
+            GPIO_LINE_FLAG_INPUT | GPIO_LINE_FLAG_BIAS_PULL_DOWN
+        );
+    }
+
+    #[test]
+    fn line_flags_active_overrides() {
+        let request = request(
+            Options::input([5, 6, 13])
+                .bias(Bias::PullUp)
+                .line_active([6], Active::Low),
+        )
+        .unwrap();
+
+        let config = &request.config;
+
+        assert_eq!(
+            config.flags,
+            GPIO_LINE_FLAG_INPUT | GPIO_LINE_FLAG_BIAS_PULL_UP
+        );
+        assert_eq!(config.num_attrs, 1);
+        assert_eq!(config.attrs[0].mask, 0b010);
+        assert_eq!(
+            unsafe { config.attrs[0].attr.val.flags },
+            GPIO_LINE_FLAG_INPUT | GPIO_LINE_FLAG_BIAS_PULL_UP | GPIO_LINE_FLAG_ACTIVE_LOW
+        );
+    }
+
+    #[test]
+    fn output_values_truncated() {
+        let request = GpioLineRequest::new(
+            &[0, 1, 2],
+            Direction::Output,
+            Active::High,
+            None,
+            None,
+            None,
+            None,
+            EventClock::Monotonic,
+            Some(Masked::from(0xffu8).convert()),
+            &[],
+            "",
+        )
+        .unwrap();
+
+        let config = &request.config;
+
+        assert_eq!(config.num_attrs, 1);
+        assert_eq!(config.attrs[0].attr.id, GPIO_LINE_ATTR_ID_OUTPUT_VALUES);
+        assert_eq!(config.attrs[0].mask, 0b111);
+        assert_eq!(unsafe { config.attrs[0].attr.val.values }, 0b111);
+    }
+
+    #[test]
+    fn input_debounce() {
+        let request = request(
+            Options::input([3, 4, 5])
+                .bias(Bias::PullUp)
+                .debounce(Time::from_millis(5)),
+        )
+        .unwrap();
+
+        let config = &request.config;
+
+        assert_eq!(config.num_attrs, 1);
+        assert_eq!(config.attrs[0].attr.id, GPIO_LINE_ATTR_ID_DEBOUNCE);
+        assert_eq!(config.attrs[0].mask, 0b111);
+        assert_eq!(unsafe { config.attrs[0].attr.val.debounce_period_us }, 5000);
+    }
+
+    #[test]
+    fn input_debounce_too_long() {
+        assert!(request(Options::input([3]).debounce(Time::from_secs(5000))).is_err());
+    }
+
+    #[test]
+    fn line_flags_bias_as_is() {
+        let as_is = request(Options::input([3]).bias(Bias::PullUp).bias_as_is()).unwrap();
+
+        assert_eq!(as_is.config.flags, GPIO_LINE_FLAG_INPUT);
+
+        let disabled = request(Options::input([3]).bias(Bias::Disable)).unwrap();
+
+        assert_eq!(
+            disabled.config.flags,
+            GPIO_LINE_FLAG_INPUT | GPIO_LINE_FLAG_BIAS_DISABLED
+        );
+    }
+
+    #[test]
+    fn line_flags_same_as_request() {
+        let request = request(
+            Options::input([0, 1, 2, 3, 4, 5, 6, 7])
+                .bias(Bias::PullUp)
+                .line_bias([2, 3], Bias::PullUp),
+        )
+        .unwrap();
+
+        assert_eq!(request.config.num_attrs, 0);
+    }
+
+    #[test]
+    fn line_flags_too_many_attrs() {
+        let edges = [
+            EdgeDetect::Disable,
+            EdgeDetect::Rising,
+            EdgeDetect::Falling,
+            EdgeDetect::Both,
+        ];
+        let biases = [Bias::PullUp, Bias::PullDown, Bias::Disable];
+
+        let mut options = Options::input((0..12).collect::<Vec<_>>());
+
+        for line in 0..12 {
+            options = options
+                .line_edge([line], edges[line as usize % 4])
+                .line_bias([line], biases[line as usize / 4]);
+        }
+
+        assert!(request(options).is_err());
+    }
+
+    #[test]
+    fn line_flags_unknown_line() {
+        assert!(
+            request(Options::input([0, 1, 2, 3, 4, 5, 6, 7]).line_bias([8], Bias::PullUp)).is_err()
+        );
+    }
+
+    #[test]
+    fn line_info_debounce() {
+        let mut info = GpioLineInfo::default();
+        info.num_attrs = 1;
+        info.flags = GPIO_LINE_FLAG_INPUT;
+        info.attrs[0].id = GPIO_LINE_ATTR_ID_DEBOUNCE;
+        info.attrs[0].val.debounce_period_us = 5000;
+
+        let info = info.as_info().unwrap();
+
+        assert_eq!(info.debounce, Some(Time::from_millis(5)));
+        assert_eq!(
+            info.attributes,
+            [LineAttribute::Debounce(Time::from_millis(5))]
+        );
+
+        let info = GpioLineInfo::default().as_info().unwrap();
+
+        assert_eq!(info.debounce, None);
+    }
+
+    #[test]
+    fn line_attributes() {
+        let mut attr = GpioLineAttr::default();
+        attr.id = GPIO_LINE_ATTR_ID_FLAGS;
+        attr.val.flags = GPIO_LINE_FLAG_INPUT;
+
+        assert_eq!(
+            attr.as_attribute(),
+            LineAttribute::Flags(GPIO_LINE_FLAG_INPUT)
+        );
+
+        attr.id = GPIO_LINE_ATTR_ID_OUTPUT_VALUES;
+        attr.val.values = 0b101;
+
+        assert_eq!(attr.as_attribute(), LineAttribute::OutputValues(0b101));
+
+        attr.id = 42;
+
+        assert_eq!(
+            attr.as_attribute(),
+            LineAttribute::Unknown {
+                id: 42,
+                value: 0b101
+            }
+        );
+    }
+
+    #[test]
+    fn line_config_overrides() {
+        let request = request(
+            Options::input([2, 3, 4])
+                .line_config(3, LineConfig::default().active(Active::Low))
+                .line_config(4, LineConfig::default().edge(EdgeDetect::Rising))
+                .line_config(4, LineConfig::default().bias(Bias::PullDown)),
+        )
+        .unwrap();
+
+        let config = &request.config;
+
+        assert_eq!(config.flags, GPIO_LINE_FLAG_INPUT);
+        assert_eq!(config.num_attrs, 2);
+        assert_eq!(config.attrs[0].mask, 0b010);
+        assert_eq!(
+            unsafe { config.attrs[0].attr.val.flags },
+            GPIO_LINE_FLAG_INPUT | GPIO_LINE_FLAG_ACTIVE_LOW
+        );
+        assert_eq!(config.attrs[1].mask, 0b100);
+        assert_eq!(
+            unsafe { config.attrs[1].attr.val.flags },
+            GPIO_LINE_FLAG_INPUT | GPIO_LINE_FLAG_EDGE_RISING | GPIO_LINE_FLAG_BIAS_PULL_DOWN
+        );
+    }
+
+    #[test]
+    fn line_config_too_many() {
+        let configs = [Active::High, Active::Low]
+            .into_iter()
+            .flat_map(|active| {
+                [Bias::PullUp, Bias::PullDown, Bias::Disable]
+                    .into_iter()
+                    .flat_map(move |bias| {
+                        [EdgeDetect::Rising, EdgeDetect::Falling]
+                            .map(|edge| LineConfig::default().active(active).bias(bias).edge(edge))
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        let lines = (0..configs.len() as LineId).collect::<Vec<_>>();
+
+        let options = configs
+            .iter()
+            .zip(&lines)
+            .fold(Options::input(&lines), |options, (config, line)| {
+                options.line_config(*line, *config)
+            });
+
+        let error = request(options).err().unwrap();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn input_event_clock() {
+        let request = request(
+            Options::input([1, 2])
+                .edge(EdgeDetect::Both)
+                .event_clock(EventClock::Realtime),
+        )
+        .unwrap();
+
+        assert_eq!(
+            request.config.flags,
+            GPIO_LINE_FLAG_INPUT | GPIO_LINE_FLAG_EDGE_BOTH | GPIO_LINE_FLAG_EVENT_CLOCK_REALTIME
+        );
     }
 }
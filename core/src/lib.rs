@@ -3,28 +3,105 @@
 #[cfg(not(target_os = "linux"))]
 compile_error!("This crate support Linux only");
 
+mod error;
 mod iop;
 mod raw;
 mod types;
 mod utils;
 mod values;
 
-use std::{fmt, os::unix::io::RawFd};
-
-pub use iop::RawEvent;
-pub use std::{
-    io::{Error, Result},
-    time::Duration as Time,
+use std::{
+    collections::HashMap,
+    fmt, io,
+    os::unix::io::{AsRawFd, BorrowedFd, RawFd},
+    sync::{Arc, Mutex, OnceLock},
 };
+
+pub use error::{Error, Result};
+pub use iop::{RawEvent, RawInfoChange};
+pub use std::time::Duration as Time;
 pub use types::{
-    Active, Bias, BitId, Direction, Drive, Edge, EdgeDetect, Event, LineId, LineInfo, LineMap,
+    AbiVersion, Active, Bias, BitId, BitOrder, Direction, Drive, Edge, EdgeDetect, Event,
+    EventClock, EventRecord, EventStats, LineAttribute, LineCapabilities, LineChange,
+    LineChangeKind, LineEventStats, LineId, LineInfo, LineMap, NameMatch, TimeFormat,
 };
 pub use utils::*;
-pub use values::{AsValues, AsValuesMut, Bits, Masked, Values, MAX_BITS, MAX_VALUES};
+pub use values::{
+    AsValues, AsValuesMut, Bits, Masked, MaskedIter, Values, WidthValues, MAX_BITS, MAX_VALUES,
+};
+
+/// Maximum length of consumer string in bytes
+pub const MAX_CONSUMER_LEN: usize = raw::GPIO_MAX_NAME_SIZE - 1;
+
+fn check_consumer(consumer: &str) -> Result<()> {
+    if consumer.len() > MAX_CONSUMER_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "Consumer string too long ({} bytes, max {} bytes)",
+                consumer.len(),
+                MAX_CONSUMER_LEN
+            ),
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Get the version of GPIO ABI which is used to interface with kernel
+///
+/// Currently it is selected at compile time using `v2` feature.
+pub fn abi_version() -> AbiVersion {
+    if cfg!(feature = "v2") {
+        AbiVersion::V2
+    } else {
+        AbiVersion::V1
+    }
+}
+
+/// Get the version of GPIO ABI from the result of probing ABI v2 line info ioctl
+///
+/// Unknown ioctl means that kernel supports ABI v1 only (older kernels reports `EINVAL`
+/// while newer reports `ENOTTY`). Since `EINVAL` is also reported for out of range offset,
+/// it is inconclusive when chip has no lines to probe.
+fn probe_abi(result: nix::Result<nix::libc::c_int>, has_lines: bool) -> Result<AbiVersion> {
+    match result {
+        Ok(_) => Ok(AbiVersion::V2),
+        Err(nix::errno::Errno::ENOTTY) => Ok(AbiVersion::V1),
+        Err(nix::errno::Errno::EINVAL) if has_lines => Ok(AbiVersion::V1),
+        Err(nix::errno::Errno::EINVAL) => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "GPIO ABI cannot be probed using chip without lines",
+        )
+        .into()),
+        Err(errno) => Err(errno.into()),
+    }
+}
+
+/// Replace unknown ioctl error by hint about ABI
+#[cfg(feature = "v2")]
+fn check_abi(error: Error) -> Error {
+    if error.raw_os_error() == Some(nix::libc::ENOTTY) {
+        io::Error::new(
+            io::ErrorKind::Unsupported,
+            "GPIO ABI v2 is not supported by kernel (rebuild without `v2` feature)",
+        )
+        .into()
+    } else {
+        error
+    }
+}
 
 macro_rules! unsafe_call {
     ($res:expr) => {
-        unsafe { $res }.map_err(Error::from)
+        loop {
+            match unsafe { $res } {
+                // repeat call interrupted by signal
+                Err(nix::errno::Errno::EINTR) => {}
+                res => break res.map_err(Error::from),
+            }
+        }
     };
 }
 
@@ -46,12 +123,64 @@ impl<T> core::ops::DerefMut for Internal<T> {
     }
 }
 
+/// Snapshot of request-wide configuration of lines
+///
+/// Per-line overrides are not included.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestConfig {
+    /// Associated chip name
+    pub chip_name: String,
+    /// Consumer string
+    pub consumer: String,
+    /// Offsets of requested lines
+    pub lines: Vec<LineId>,
+    /// Direction of lines
+    pub direction: Direction,
+    /// Active state
+    pub active: Active,
+    /// Edge detection (inputs only)
+    pub edge: Option<EdgeDetect>,
+    /// Bias
+    pub bias: Option<Bias>,
+    /// Drive mode (outputs only)
+    pub drive: Option<Drive>,
+    /// Initial values (outputs only)
+    pub values: Option<Values>,
+    /// Kernel event buffer size
+    pub event_buffer: Option<u32>,
+    /// Clock to timestamp events
+    pub event_clock: EventClock,
+    /// Input debounce period
+    pub debounce: Option<Time>,
+}
+
 /// GPIO lines values interface info
 pub struct ValuesInfo {
     chip_name: String,
     consumer: String,
     lines: Vec<LineId>,
     index: LineMap,
+    active: Active,
+    edge: Option<EdgeDetect>,
+    bias: Option<Bias>,
+    drive: Option<Drive>,
+    values: Option<Values>,
+    overrides: Vec<LineOverride>,
+    event_buffer: Option<u32>,
+    event_clock: EventClock,
+    debounce: Option<Time>,
+    /// Per-line event fds (v1 ABI only)
+    #[cfg(not(feature = "v2"))]
+    events: Vec<std::fs::File>,
+    registration: Option<LineRegistration>,
+    chip: Option<LinesChip>,
+}
+
+/// Chip which lines was requested from
+struct LinesChip {
+    info: Internal<ChipInfo>,
+    // duplicated chip fd to query lines without re-opening chip
+    file: std::fs::File,
 }
 
 impl fmt::Display for ValuesInfo {
@@ -80,31 +209,387 @@ impl ValuesInfo {
     pub fn index(&self) -> &LineMap {
         &self.index
     }
+
+    /// Get requested active state
+    pub fn active(&self) -> Active {
+        self.active
+    }
+
+    /// Get requested edge detection
+    pub fn edge(&self) -> Option<EdgeDetect> {
+        self.edge
+    }
+
+    /// Get requested bias
+    pub fn bias(&self) -> Option<Bias> {
+        self.bias
+    }
+
+    /// Get requested drive mode
+    pub fn drive(&self) -> Option<Drive> {
+        self.drive
+    }
+
+    /// Get requested event buffer size
+    pub fn event_buffer(&self) -> Option<u32> {
+        self.event_buffer
+    }
+
+    /// Get requested event clock
+    pub fn event_clock(&self) -> EventClock {
+        self.event_clock
+    }
+
+    /// Get requested debounce period
+    pub fn debounce(&self) -> Option<Time> {
+        self.debounce
+    }
+
+    /// Get requested default values
+    pub fn values(&self) -> Option<Values> {
+        self.values
+    }
+
+    /// Get values which toggles lines
+    ///
+    /// Inverts the given current values of all requested lines or only the value of given line
+    /// keeping others as is. Logical values are inverted, so active-low lines are toggled too.
+    pub fn toggled_values(&self, mut values: Values, line: Option<LineId>) -> Result<Values> {
+        values.truncate(self.lines.len() as _);
+
+        Ok(match line {
+            Some(line) => {
+                let id = self.index.find(line)?;
+                let value = values.get(id).unwrap_or(false);
+                values.with(id, Some(!value))
+            }
+            None => !values,
+        })
+    }
+
+    /// Check that the edge of line is detected
+    ///
+    /// Fails when the line is not requested or edge detection of line (taking per-line
+    /// options into account) does not cover the edge.
+    pub fn check_edge(&self, line: LineId, edge: Edge) -> Result<()> {
+        self.index.find(line)?;
+
+        let detect = self
+            .overrides
+            .iter()
+            .find(|item| item.line == line)
+            .and_then(|item| item.edge)
+            .or(self.edge)
+            .unwrap_or(EdgeDetect::Disable);
+
+        let detected = match detect {
+            EdgeDetect::Disable => false,
+            EdgeDetect::Both => true,
+            EdgeDetect::Rising => edge == Edge::Rising,
+            EdgeDetect::Falling => edge == Edge::Falling,
+        };
+
+        if !detected {
+            return Err(invalid_input("Edge detection does not match"));
+        }
+
+        Ok(())
+    }
+
+    /// Iterate over values paired with line offsets
+    ///
+    /// The values should be given in the order of requested lines (like returned by
+    /// `get_values`). The values which are not set are yielded as `None`.
+    ///
+    /// ```
+    /// # use gpiod_core::{Masked, ValuesInfo};
+    /// // for lines [27, 3, 11] and values 0b1x1 yields
+    /// // (27, Some(true)), (3, None), (11, Some(true))
+    /// fn print_values(info: &ValuesInfo, values: Masked<u8>) {
+    ///     for (line, value) in info.iter_values(&values) {
+    ///         match value {
+    ///             Some(value) => println!("{line}: {value}"),
+    ///             None => println!("{line}: unset"),
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn iter_values<'a, V: AsValues>(
+        &'a self,
+        values: &'a V,
+    ) -> impl Iterator<Item = (LineId, Option<bool>)> + 'a {
+        self.lines.iter().map(move |&line| {
+            let value = self.index.get(line).ok().and_then(|id| values.get(id));
+            (line, value)
+        })
+    }
+
+    /// Format values labeled by line names
+    ///
+    /// The names should be given in the order of requested lines.
+    /// When name is missing or empty the line offset will be used instead.
+    /// The values which are not set will be formatted as `x`,
+    /// so result looks like `status-led=1 7=0 button=x`.
+    pub fn format_values(&self, values: impl AsValues, names: &[String]) -> String {
+        use std::fmt::Write;
+
+        let mut out = String::new();
+
+        for (id, line) in self.lines.iter().enumerate() {
+            if id > 0 {
+                out.push(' ');
+            }
+
+            match names.get(id).filter(|name| !name.is_empty()) {
+                Some(name) => out.push_str(name),
+                None => write!(out, "{line}").unwrap(),
+            }
+
+            out.push('=');
+
+            out.push(match values.get(id as _) {
+                Some(true) => '1',
+                Some(false) => '0',
+                None => 'x',
+            });
+        }
+
+        out
+    }
 }
 
 impl Internal<ValuesInfo> {
-    fn new(chip_name: &str, consumer: &str, lines: &[LineId]) -> Self {
+    /// Create values info using lines from options
+    ///
+    /// Unlike [Internal::<ChipInfo>::request_lines] it does not request lines from kernel,
+    /// so it can be used to simulate requests (line names are not resolved).
+    pub fn new<Direction>(
+        chip_name: &str,
+        options: &Options<Direction, impl AsRef<[LineId]>, impl AsRef<str>>,
+    ) -> Result<Self> {
+        Self::with_lines(chip_name, options.lines.as_ref(), options)
+    }
+
+    fn with_lines<Direction>(
+        chip_name: &str,
+        lines: &[LineId],
+        options: &Options<Direction, impl AsRef<[LineId]>, impl AsRef<str>>,
+    ) -> Result<Self> {
         let chip_name = chip_name.into();
-        let consumer = consumer.into();
+        let consumer = options.consumer.as_ref().into();
         let index = LineMap::new(lines);
         let lines = lines.to_owned();
 
-        Self(ValuesInfo {
+        let mut values = options.values;
+
+        if !options.value_pairs.is_empty() {
+            let values = values.get_or_insert_with(Values::default);
+
+            for &(line, value) in &options.value_pairs {
+                values.set(index.find(line)?, Some(value));
+            }
+        }
+
+        // Bits above the number of lines does not correspond to any requested line
+        if let Some(values) = &mut values {
+            values.truncate(lines.len() as _);
+        }
+
+        Ok(Self(ValuesInfo {
             chip_name,
             consumer,
             lines,
             index,
-        })
+            active: options.active,
+            edge: options.edge,
+            bias: options.bias,
+            drive: options.drive,
+            values,
+            overrides: options.overrides.clone(),
+            event_buffer: options.event_buffer,
+            event_clock: options.event_clock,
+            debounce: options.debounce,
+            #[cfg(not(feature = "v2"))]
+            events: Vec::new(),
+            registration: None,
+            chip: None,
+        }))
     }
 
-    pub fn get_values<T: AsValuesMut>(&self, fd: RawFd, values: &mut T) -> Result<()> {
+    /// Reconstruct options which was used to request lines
+    pub fn options<Direction>(&self) -> Options<Direction, Vec<LineId>, String> {
+        Options::<Direction, Vec<LineId>, String> {
+            lines: self.lines.clone(),
+            direction: Default::default(),
+            active: self.active,
+            edge: self.edge,
+            bias: self.bias,
+            drive: self.drive,
+            values: self.values,
+            value_pairs: Default::default(),
+            overrides: self.overrides.clone(),
+            event_buffer: self.event_buffer,
+            event_clock: self.event_clock,
+            debounce: self.debounce,
+            names: Default::default(),
+            consumer: self.consumer.clone(),
+        }
+    }
+
+    /// Read actual info of requested lines from chip
+    ///
+    /// The chip descriptor which was duplicated when lines requested is used,
+    /// so it works without access to chip device.
+    pub fn lines_info(&self) -> Result<Vec<LineInfo>> {
+        let chip = self.chip()?;
+
+        chip.info.lines_info(chip.file.as_raw_fd(), &self.lines)
+    }
+
+    /// Get names of requested lines
+    ///
+    /// The names are in order of requested lines (unnamed lines has empty names).
+    /// The names are cached by chip, so info of lines is read on first call only.
+    pub fn line_names(&self) -> Result<Vec<String>> {
+        let chip = self.chip()?;
+
+        chip.info.line_names(chip.file.as_raw_fd(), &self.lines)
+    }
+
+    fn chip(&self) -> Result<&LinesChip> {
+        self.chip
+            .as_ref()
+            .ok_or_else(|| invalid_input("Lines was not requested from chip"))
+    }
+
+    /// Get snapshot of request configuration
+    ///
+    /// Reflects changes made using [Internal::<ValuesInfo>::reconfigure].
+    pub fn request_config<Direction: DirectionType>(&self) -> RequestConfig {
+        RequestConfig {
+            chip_name: self.chip_name.clone(),
+            consumer: self.consumer.clone(),
+            lines: self.lines.clone(),
+            direction: Direction::DIR,
+            active: self.active,
+            edge: self.edge,
+            bias: self.bias,
+            drive: self.drive,
+            values: self.values,
+            event_buffer: self.event_buffer,
+            event_clock: self.event_clock,
+            debounce: self.debounce,
+        }
+    }
+
+    /// Change configuration of requested lines without releasing it
+    ///
+    /// The lines should be same as requested, the consumer string and event buffer size
+    /// cannot be changed so ignored. With ABI v1 edge detection and per-line options
+    /// cannot be reconfigured.
+    pub fn reconfigure<Direction: DirectionType>(
+        &mut self,
+        fd: RawFd,
+        options: &Options<Direction, impl AsRef<[LineId]>, impl AsRef<str>>,
+    ) -> Result<()> {
+        if options.lines.as_ref() != self.lines.as_slice() {
+            return Err(invalid_input("Requested lines cannot be changed"));
+        }
+
+        options.validate()?;
+
+        let info = Self::with_lines(&self.chip_name, &self.lines, options)?.0;
+
+        let direction = Direction::DIR;
+
         #[cfg(not(feature = "v2"))]
         {
+            if !info.overrides.is_empty() {
+                return Err(invalid_input("Per-line options not supported"));
+            }
+
+            if info.debounce.is_some() {
+                return Err(invalid_input("Debounce not supported"));
+            }
+
+            if matches!(info.event_clock, EventClock::Realtime) {
+                return Err(invalid_input("Event clock not supported"));
+            }
+
+            if !self.events.is_empty()
+                || info
+                    .edge
+                    .map(|edge| !matches!(edge, EdgeDetect::Disable))
+                    .unwrap_or(false)
+            {
+                return Err(invalid_input("Edge detection cannot be reconfigured"));
+            }
+
+            let mut config = raw::v1::GpioHandleConfig::new(
+                self.lines.len(),
+                direction,
+                info.active,
+                info.bias,
+                info.drive,
+                info.values,
+            );
+
+            unsafe_call!(raw::v1::gpio_set_config(fd, &mut config))?;
+        }
+
+        #[cfg(feature = "v2")]
+        {
+            let mut config = raw::v2::GpioLineConfig::new(
+                &self.lines,
+                direction,
+                info.active,
+                info.edge,
+                info.bias,
+                info.drive,
+                info.debounce,
+                info.event_clock,
+                info.values,
+                &info.overrides,
+            )?;
+
+            unsafe_call!(raw::v2::gpio_line_set_config(fd, &mut config))?;
+        }
+
+        self.0.active = info.active;
+        self.0.edge = info.edge;
+        self.0.bias = info.bias;
+        self.0.drive = info.drive;
+        self.0.debounce = info.debounce;
+        self.0.event_clock = info.event_clock;
+        self.0.values = info.values;
+        self.0.overrides = info.overrides;
+
+        Ok(())
+    }
+
+    /// Get values of requested lines
+    ///
+    /// Only the values which fits into buffer will be read. The values in buffer above
+    /// the number of requested lines will be cleared (reset to `false` for booleans).
+    pub fn get_values<T: AsValuesMut>(&self, fd: RawFd, values: &mut T) -> Result<()> {
+        #[cfg(not(feature = "v2"))]
+        if self.events.is_empty() {
             let mut data = raw::v1::GpioHandleData::default();
 
             unsafe_call!(raw::v1::gpio_get_line_values(fd, &mut data))?;
 
             data.fill_values(self.lines.len(), values);
+        } else {
+            use std::os::unix::io::AsRawFd;
+
+            // Each event fd supports reading the value of its own line
+            for (id, event) in self.events.iter().enumerate() {
+                let mut data = raw::v1::GpioHandleData::default();
+
+                unsafe_call!(raw::v1::gpio_get_line_values(event.as_raw_fd(), &mut data))?;
+
+                values.set(id as _, Some(data.values[0] != 0));
+            }
         }
 
         #[cfg(feature = "v2")]
@@ -117,9 +602,21 @@ impl Internal<ValuesInfo> {
             data.copy_into(values);
         }
 
+        self.truncate_values(values);
+
         Ok(())
     }
 
+    /// Clear values above the number of requested lines
+    ///
+    /// Plain booleans cannot be unset, so it will be reset to `false`.
+    fn truncate_values<T: AsValuesMut>(&self, values: &mut T) {
+        for id in self.lines.len() as BitId..values.bits() {
+            values.set(id, Some(false));
+            values.set(id, None);
+        }
+    }
+
     pub fn set_values<T: AsValues>(&self, fd: RawFd, values: T) -> Result<()> {
         #[cfg(not(feature = "v2"))]
         {
@@ -138,6 +635,156 @@ impl Internal<ValuesInfo> {
 
         Ok(())
     }
+
+    /// Get value of single line
+    ///
+    /// Fails when the line is not requested.
+    pub fn get_value(&self, fd: RawFd, line: LineId) -> Result<bool> {
+        let id = self.index.find(line)?;
+
+        let mut values = Values::default();
+        values.fill(0..self.lines.len() as _, Some(false));
+
+        self.get_values(fd, &mut values)?;
+
+        Ok(values.get(id).unwrap_or(false))
+    }
+
+    /// Set value of single line keeping values of other lines
+    ///
+    /// Fails when the line is not requested. With ABI v1 the current values are read
+    /// and written back with the line changed, while ABI v2 changes the line only.
+    pub fn set_value(&self, fd: RawFd, line: LineId, value: bool) -> Result<()> {
+        let id = self.index.find(line)?;
+
+        #[cfg(not(feature = "v2"))]
+        let values = {
+            let mut values = Values::default();
+            values.fill(0..self.lines.len() as _, Some(false));
+
+            self.get_values(fd, &mut values)?;
+
+            values
+        };
+
+        #[cfg(feature = "v2")]
+        let values = Values::default();
+
+        self.set_values(fd, values.with(id, Some(value)))
+    }
+
+    /// Toggle values of all requested lines or only the given line
+    ///
+    /// The current values are read and written back inverted (see [ValuesInfo::toggled_values]).
+    pub fn toggle_values(&self, fd: RawFd, line: Option<LineId>) -> Result<()> {
+        let mut values = Values::default();
+        values.fill(0..self.lines.len() as _, Some(false));
+
+        self.get_values(fd, &mut values)?;
+
+        self.set_values(fd, self.toggled_values(values, line)?)
+    }
+
+    /// Create guard which writes inverse of given values on drop
+    ///
+    /// Useful to return lines to previous state when operation is interrupted.
+    pub fn restore_guard(&self, fd: RawFd, values: Values) -> Result<RestoreValues<'_>> {
+        Ok(RestoreValues {
+            info: self,
+            fd,
+            values: Some(self.toggled_values(values, None)?),
+        })
+    }
+
+    /// Read pending edge event using epoll fd returned by [Internal::<ChipInfo>::request_lines]
+    ///
+    /// Returns [std::io::ErrorKind::WouldBlock] error when no events available.
+    #[cfg(not(feature = "v2"))]
+    pub fn read_event(&self, fd: RawFd) -> Result<Event> {
+        use nix::sys::epoll::{epoll_wait, EpollEvent};
+        use std::io::Read;
+
+        if self.events.is_empty() {
+            return Err(invalid_input("Edge detection not configured"));
+        }
+
+        let mut ready = [EpollEvent::empty()];
+
+        if epoll_wait(fd, &mut ready, 0)? == 0 {
+            return Err(std::io::ErrorKind::WouldBlock.into());
+        }
+
+        let id = ready[0].data() as usize;
+        let file = self
+            .events
+            .get(id)
+            .ok_or_else(|| invalid_data("Unknown event source"))?;
+
+        let mut event = RawEvent::default();
+
+        check_size(
+            retry_interrupted(|| Ok((&*file).read(event.as_mut())?))?,
+            &event,
+        )?;
+
+        event.as_event(id as _, self.lines[id])
+    }
+}
+
+/// Decoder of edge events from bytes read from lines fd
+///
+/// The bytes of partially read event are kept until the rest of event arrives.
+#[cfg(feature = "v2")]
+#[derive(Debug, Default)]
+pub struct EventBuffer {
+    pending: Vec<u8>,
+}
+
+#[cfg(feature = "v2")]
+impl EventBuffer {
+    const EVENT_SIZE: usize = core::mem::size_of::<RawEvent>();
+
+    /// Get number of bytes which should be read to get up to `max` events
+    ///
+    /// Kernel rejects reads shorter than single event, so whole events are read always
+    /// even when partially read event is pending. Bytes which does not complete events
+    /// are kept in buffer, so no more than `max` events are decoded at once.
+    pub fn read_len(&self, max: usize) -> usize {
+        max.max(1) * Self::EVENT_SIZE
+    }
+
+    /// Decode events from bytes appending it to `events`
+    ///
+    /// Returns number of decoded events.
+    pub fn decode(
+        &mut self,
+        data: &[u8],
+        index: &LineMap,
+        clock: EventClock,
+        events: &mut Vec<Event>,
+    ) -> Result<usize> {
+        self.pending.extend_from_slice(data);
+
+        let len = self.pending.len() / Self::EVENT_SIZE * Self::EVENT_SIZE;
+
+        let decoded = self.pending[..len]
+            .chunks_exact(Self::EVENT_SIZE)
+            .map(|chunk| {
+                let mut event = RawEvent::default();
+                event.as_mut().copy_from_slice(chunk);
+                event.as_event(index, clock)
+            })
+            .collect::<Result<Vec<_>>>();
+
+        self.pending.drain(..len);
+
+        let decoded = decoded?;
+        let count = decoded.len();
+
+        events.extend(decoded);
+
+        Ok(count)
+    }
 }
 
 /// Direction trait
@@ -189,6 +836,55 @@ impl DirectionType for Output {
 ///     .edge(EdgeDetect::Both)
 ///     .consumer("my inputs");
 /// ```
+///
+/// Input lines identified by names:
+/// ```
+/// # use gpiod_core::{Options, Bias};
+/// let input = Options::input_named(["GPIO17", "GPIO27"])
+///     .bias(Bias::PullUp)
+///     .consumer("my inputs");
+/// ```
+///
+/// Input with different bias per line (ABI v2 only):
+/// ```
+/// # use gpiod_core::{Options, Bias};
+/// let input = Options::input(&[0, 1, 2, 3, 4, 5, 6, 7])
+///     .line_bias(&[0, 1, 2, 3], Bias::PullUp)
+///     .line_bias(&[4, 5, 6, 7], Bias::PullDown)
+///     .consumer("my inputs");
+/// ```
+///
+/// Buttons with different active state on the same connector (ABI v2 only):
+/// ```
+/// # use gpiod_core::{Options, Active};
+/// let input = Options::input(&[5, 6, 13])
+///     .line_active(&[6], Active::Low)
+///     .consumer("my buttons");
+/// ```
+///
+/// Active state and bias are shared by inputs and outputs, but edge detection can be configured
+/// for inputs only while drive mode and default values can be configured for outputs only.
+///
+/// Edge detection for outputs is rejected at compile time:
+/// ```compile_fail
+/// # use gpiod_core::{Options, EdgeDetect};
+/// let output = Options::output(&[11, 20])
+///     .edge(EdgeDetect::Both);
+/// ```
+///
+/// Drive mode for inputs is rejected at compile time:
+/// ```compile_fail
+/// # use gpiod_core::{Options, Drive};
+/// let input = Options::input(&[23, 17])
+///     .drive(Drive::OpenDrain);
+/// ```
+///
+/// Default values for inputs is rejected at compile time:
+/// ```compile_fail
+/// # use gpiod_core::Options;
+/// let input = Options::input(&[23, 17])
+///     .values([false, true]);
+/// ```
 pub struct Options<Direction = (), Lines = (), Consumer = ()> {
     lines: Lines,
     direction: core::marker::PhantomData<Direction>,
@@ -197,37 +893,147 @@ pub struct Options<Direction = (), Lines = (), Consumer = ()> {
     bias: Option<Bias>,
     drive: Option<Drive>,
     values: Option<Values>,
+    value_pairs: Vec<(LineId, bool)>,
+    overrides: Vec<LineOverride>,
+    event_buffer: Option<u32>,
+    event_clock: EventClock,
+    debounce: Option<Time>,
+    names: Vec<String>,
     consumer: Consumer,
 }
 
-impl Options {
-    /// Create input options
-    pub fn input<Lines: AsRef<[LineId]>>(lines: Lines) -> Options<Input, Lines, &'static str> {
-        Options::<Input, Lines, &'static str> {
-            lines,
-            direction: Default::default(),
-            active: Default::default(),
-            edge: Default::default(),
-            bias: Default::default(),
-            drive: Default::default(),
-            values: Default::default(),
-            consumer: "",
-        }
+/// Configuration of specific GPIO line
+///
+/// Unset options falls back to request-wide ones, see [Options::line_config].
+/// Direction is always request-wide and cannot be configured per line.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LineConfig {
+    /// Active state
+    pub active: Option<Active>,
+    /// Edge detection (inputs only)
+    pub edge: Option<EdgeDetect>,
+    /// Bias
+    pub bias: Option<Bias>,
+    /// Drive mode (outputs only)
+    pub drive: Option<Drive>,
+}
+
+impl LineConfig {
+    /// Configure active state
+    pub fn active(mut self, active: Active) -> Self {
+        self.active = Some(active);
+        self
     }
 
-    /// Create output options
-    pub fn output<Lines: AsRef<[LineId]>>(lines: Lines) -> Options<Output, Lines, &'static str> {
-        Options::<Output, Lines, &'static str> {
-            lines,
-            direction: Default::default(),
-            active: Default::default(),
-            edge: Default::default(),
-            bias: Default::default(),
-            drive: Default::default(),
+    /// Configure edge detection
+    pub fn edge(mut self, edge: EdgeDetect) -> Self {
+        self.edge = Some(edge);
+        self
+    }
+
+    /// Configure bias
+    pub fn bias(mut self, bias: Bias) -> Self {
+        self.bias = Some(bias);
+        self
+    }
+
+    /// Configure drive mode
+    pub fn drive(mut self, drive: Drive) -> Self {
+        self.drive = Some(drive);
+        self
+    }
+}
+
+/// Options which overrides request-wide options for specific line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct LineOverride {
+    pub line: LineId,
+    pub active: Option<Active>,
+    pub edge: Option<EdgeDetect>,
+    pub bias: Option<Bias>,
+    pub drive: Option<Drive>,
+}
+
+impl LineOverride {
+    fn new(line: LineId) -> Self {
+        Self {
+            line,
+            active: None,
+            edge: None,
+            bias: None,
+            drive: None,
+        }
+    }
+}
+
+impl Options {
+    /// Create input options
+    pub fn input<Lines: AsRef<[LineId]>>(lines: Lines) -> Options<Input, Lines, &'static str> {
+        Options::<Input, Lines, &'static str> {
+            lines,
+            direction: Default::default(),
+            active: Default::default(),
+            edge: Default::default(),
+            bias: Default::default(),
+            drive: Default::default(),
+            values: Default::default(),
+            value_pairs: Default::default(),
+            overrides: Default::default(),
+            event_buffer: Default::default(),
+            event_clock: Default::default(),
+            debounce: Default::default(),
+            names: Default::default(),
+            consumer: "",
+        }
+    }
+
+    /// Create output options
+    pub fn output<Lines: AsRef<[LineId]>>(lines: Lines) -> Options<Output, Lines, &'static str> {
+        Options::<Output, Lines, &'static str> {
+            lines,
+            direction: Default::default(),
+            active: Default::default(),
+            edge: Default::default(),
+            bias: Default::default(),
+            drive: Default::default(),
             values: Default::default(),
+            value_pairs: Default::default(),
+            overrides: Default::default(),
+            event_buffer: Default::default(),
+            event_clock: Default::default(),
+            debounce: Default::default(),
+            names: Default::default(),
             consumer: "",
         }
     }
+
+    /// Create input options using line names instead of offsets
+    ///
+    /// Names will be resolved to offsets when requesting lines.
+    pub fn input_named<Name: AsRef<str>>(
+        names: impl IntoIterator<Item = Name>,
+    ) -> Options<Input, Vec<LineId>, &'static str> {
+        Options::input(Vec::new()).names(names)
+    }
+
+    /// Create output options using line names instead of offsets
+    ///
+    /// Names will be resolved to offsets when requesting lines.
+    pub fn output_named<Name: AsRef<str>>(
+        names: impl IntoIterator<Item = Name>,
+    ) -> Options<Output, Vec<LineId>, &'static str> {
+        Options::output(Vec::new()).names(names)
+    }
+}
+
+impl<Direction, Consumer> Options<Direction, Vec<LineId>, Consumer> {
+    fn names<Name: AsRef<str>>(mut self, names: impl IntoIterator<Item = Name>) -> Self {
+        self.names = names
+            .into_iter()
+            .map(|name| name.as_ref().to_owned())
+            .collect();
+        self
+    }
 }
 
 impl<Direction, Lines, OldConsumer> Options<Direction, Lines, OldConsumer> {
@@ -244,11 +1050,44 @@ impl<Direction, Lines, OldConsumer> Options<Direction, Lines, OldConsumer> {
             bias: self.bias,
             drive: self.drive,
             values: self.values,
+            value_pairs: self.value_pairs,
+            overrides: self.overrides,
+            event_buffer: self.event_buffer,
+            event_clock: self.event_clock,
+            debounce: self.debounce,
+            names: self.names,
             consumer,
         }
     }
 }
 
+impl<Direction, OldLines, Consumer> Options<Direction, OldLines, Consumer> {
+    /// Configure lines offsets
+    ///
+    /// Replaces lines (or line names) which was given to constructor.
+    pub fn lines<Lines: AsRef<[LineId]>>(
+        self,
+        lines: Lines,
+    ) -> Options<Direction, Lines, Consumer> {
+        Options::<Direction, Lines, Consumer> {
+            lines,
+            direction: self.direction,
+            active: self.active,
+            edge: self.edge,
+            bias: self.bias,
+            drive: self.drive,
+            values: self.values,
+            value_pairs: self.value_pairs,
+            overrides: self.overrides,
+            event_buffer: self.event_buffer,
+            event_clock: self.event_clock,
+            debounce: self.debounce,
+            names: Default::default(),
+            consumer: self.consumer,
+        }
+    }
+}
+
 impl<Direction, Lines, Consumer> Options<Direction, Lines, Consumer> {
     /// Configure GPIO lines astive state
     ///
@@ -261,10 +1100,145 @@ impl<Direction, Lines, Consumer> Options<Direction, Lines, Consumer> {
     /// Configure GPIO lines bias
     ///
     /// Available both for inputs and outputs
+    ///
+    /// When bias is not configured the request does not touch it, so the lines keep
+    /// the bias which is currently set (or the default of driver). Configuring
+    /// [Bias::Disable] explicitly disables bias (sets the bias disabled flag).
     pub fn bias(mut self, bias: Bias) -> Self {
         self.bias = Some(bias);
         self
     }
+
+    /// Keep GPIO lines bias as is
+    ///
+    /// Resets bias configured using [Options::bias], so the request does not touch it.
+    /// This is the default.
+    pub fn bias_as_is(mut self) -> Self {
+        self.bias = None;
+        self
+    }
+
+    /// Configure active state for specific GPIO lines
+    ///
+    /// Overrides request-wide active state for the given lines, so active-high and active-low
+    /// lines can be mixed in single request.
+    /// Available both for inputs and outputs. Supported by ABI v2 only.
+    pub fn line_active(self, lines: impl AsRef<[LineId]>, active: Active) -> Self {
+        self.line_override(lines, |line| line.active = Some(active))
+    }
+
+    /// Configure bias for specific GPIO lines
+    ///
+    /// Overrides request-wide bias for the given lines.
+    /// Available both for inputs and outputs. Supported by ABI v2 only.
+    pub fn line_bias(self, lines: impl AsRef<[LineId]>, bias: Bias) -> Self {
+        self.line_override(lines, |line| line.bias = Some(bias))
+    }
+
+    /// Configure specific GPIO line
+    ///
+    /// Options which are set in config overrides request-wide ones for the given line, so
+    /// lines with different configuration can be requested atomically.
+    /// Edge detection is valid for inputs and drive mode for outputs only.
+    /// Supported by ABI v2 only, which limits number of distinct line configurations.
+    pub fn line_config(self, line: LineId, config: LineConfig) -> Self {
+        self.line_override([line], |item| {
+            if let Some(active) = config.active {
+                item.active = Some(active);
+            }
+            if let Some(edge) = config.edge {
+                item.edge = Some(edge);
+            }
+            if let Some(bias) = config.bias {
+                item.bias = Some(bias);
+            }
+            if let Some(drive) = config.drive {
+                item.drive = Some(drive);
+            }
+        })
+    }
+
+    fn line_override(
+        mut self,
+        lines: impl AsRef<[LineId]>,
+        apply: impl Fn(&mut LineOverride),
+    ) -> Self {
+        for &line in lines.as_ref() {
+            let index = match self.overrides.iter().position(|item| item.line == line) {
+                Some(index) => index,
+                None => {
+                    self.overrides.push(LineOverride::new(line));
+                    self.overrides.len() - 1
+                }
+            };
+            apply(&mut self.overrides[index]);
+        }
+        self
+    }
+}
+
+impl<Direction: DirectionType, Lines: AsRef<[LineId]>, Consumer>
+    Options<Direction, Lines, Consumer>
+{
+    /// Check that options are consistent
+    ///
+    /// At least one and at most [MAX_VALUES] lines should be given, while default values
+    /// given by [Options::values] should cover all lines. Values above the number of lines
+    /// are ignored (masked off on request), so integers wider than request can be used.
+    ///
+    /// Drive mode is valid for outputs only while edge detection, debounce period and
+    /// other event options are valid for inputs only. The builder methods does not allow
+    /// to misuse it, but per-line options does not depends on direction.
+    /// Lines requesting validates options, so calling it directly isn't required.
+    pub fn validate(&self) -> Result<()> {
+        let num_lines = if self.names.is_empty() {
+            self.lines.as_ref().len()
+        } else {
+            self.names.len()
+        };
+
+        if num_lines == 0 {
+            return Err(invalid_input("No lines to request"));
+        }
+
+        if num_lines > MAX_VALUES {
+            return Err(Error::TooManyLines);
+        }
+
+        if let Some(values) = &self.values {
+            if (0..num_lines as BitId).any(|id| values.get(id).is_none()) {
+                return Err(invalid_input("Values not given for all requested lines"));
+            }
+        }
+
+        match Direction::DIR {
+            crate::Direction::Input => {
+                if self.drive.is_some() || self.overrides.iter().any(|item| item.drive.is_some()) {
+                    return Err(invalid_input("Drive mode is not valid for inputs"));
+                }
+            }
+            crate::Direction::Output => {
+                let edge = |edge: Option<EdgeDetect>| {
+                    edge.map(|edge| !matches!(edge, EdgeDetect::Disable))
+                        .unwrap_or(false)
+                };
+
+                if edge(self.edge) || self.overrides.iter().any(|item| edge(item.edge)) {
+                    return Err(invalid_input("Edge detection is not valid for outputs"));
+                }
+
+                if self.debounce.is_some() {
+                    return Err(invalid_input("Debounce is not valid for outputs"));
+                }
+
+                if self.event_buffer.is_some() || self.event_clock != EventClock::default() {
+                    return Err(invalid_input("Event options are not valid for outputs"));
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl<Direction, Lines: AsRef<[LineId]>, Consumer: AsRef<str>> Options<Direction, Lines, Consumer> {
@@ -278,9 +1252,57 @@ impl<Direction, Lines: AsRef<[LineId]>, Consumer: AsRef<str>> Options<Direction,
             bias: self.bias,
             drive: self.drive,
             values: self.values,
+            value_pairs: self.value_pairs.clone(),
+            overrides: self.overrides.clone(),
+            event_buffer: self.event_buffer,
+            event_clock: self.event_clock,
+            debounce: self.debounce,
+            names: self.names.clone(),
             consumer: self.consumer.as_ref().to_owned(),
         }
     }
+
+    /// Split options into parts with at most [MAX_VALUES] lines each
+    ///
+    /// Useful to request more lines than single request allows. Default values configured
+    /// by [Options::values] can cover the first part only (it cannot hold more values),
+    /// so use [Options::values_pairs] to configure values of the rest of lines. The values
+    /// are converted to pairs, so parts are not required to have values for all lines.
+    /// Lines given by names cannot be split.
+    pub fn split(&self) -> Result<Vec<Options<Direction, Vec<LineId>, String>>> {
+        if !self.names.is_empty() {
+            return Err(invalid_input("Lines given by names cannot be split"));
+        }
+
+        if self.lines.as_ref().is_empty() {
+            return Ok(vec![self.to_owned()]);
+        }
+
+        Ok(self
+            .lines
+            .as_ref()
+            .chunks(MAX_VALUES)
+            .enumerate()
+            .map(|(index, lines)| {
+                let mut options = self.to_owned();
+                options.lines = lines.to_owned();
+                options.value_pairs.retain(|(line, _)| lines.contains(line));
+                if let Some(values) = options.values.take() {
+                    // Pairs are applied over values, so keep it first
+                    let offset = index * MAX_VALUES;
+                    let pairs = lines
+                        .iter()
+                        .enumerate()
+                        .map(|(id, &line)| (offset + id, line))
+                        .filter(|&(id, _)| id < values.bits() as usize)
+                        .filter_map(|(id, line)| values.get(id as _).map(|value| (line, value)));
+                    options.value_pairs.splice(0..0, pairs);
+                }
+                options.overrides.retain(|item| lines.contains(&item.line));
+                options
+            })
+            .collect())
+    }
 }
 
 impl<Lines, Consumer> Options<Input, Lines, Consumer> {
@@ -291,10 +1313,45 @@ impl<Lines, Consumer> Options<Input, Lines, Consumer> {
         self.edge = Some(edge);
         self
     }
+
+    /// Configure edge detection for specific GPIO lines
+    ///
+    /// Overrides request-wide edge detection for the given lines.
+    /// Available only for inputs. Supported by ABI v2 only.
+    pub fn line_edge(self, lines: impl AsRef<[LineId]>, edge: EdgeDetect) -> Self {
+        self.line_override(lines, |line| line.edge = Some(edge))
+    }
+
+    /// Configure size of kernel buffer for edge events
+    ///
+    /// When buffer overflows the oldest events will be dropped, see [Event::dropped_since].
+    /// Available only for inputs. Supported by ABI v2 only.
+    pub fn event_buffer(mut self, size: u32) -> Self {
+        self.event_buffer = Some(size);
+        self
+    }
+
+    /// Configure clock which is used to timestamp edge events
+    ///
+    /// See [Event::time] for difference between clocks.
+    /// Available only for inputs. Supported by ABI v2 only.
+    pub fn event_clock(mut self, clock: EventClock) -> Self {
+        self.event_clock = clock;
+        self
+    }
+
+    /// Configure debounce period
+    ///
+    /// The period should fit in `u32` microseconds.
+    /// Available only for inputs. Supported by ABI v2 only.
+    pub fn debounce(mut self, period: Time) -> Self {
+        self.debounce = Some(period);
+        self
+    }
 }
 
 impl<Lines, Consumer> Options<Output, Lines, Consumer> {
-    /// Configure edge detection
+    /// Configure output drive mode
     ///
     /// Available only for outputs
     pub fn drive(mut self, drive: Drive) -> Self {
@@ -302,20 +1359,55 @@ impl<Lines, Consumer> Options<Output, Lines, Consumer> {
         self
     }
 
+    /// Configure output drive mode for specific GPIO lines
+    ///
+    /// Overrides request-wide drive mode for the given lines.
+    /// Available only for outputs. Supported by ABI v2 only.
+    pub fn line_drive(self, lines: impl AsRef<[LineId]>, drive: Drive) -> Self {
+        self.line_override(lines, |line| line.drive = Some(drive))
+    }
+
     /// Configure default values
     ///
-    /// Available only for outputs
+    /// Values should be given for all lines, while values above the number of lines
+    /// are ignored. Available only for outputs
     pub fn values<T: AsValues>(mut self, values: T) -> Self {
         self.values = Some(values.convert());
         self
     }
+
+    /// Configure default values by line offsets
+    ///
+    /// Unlike [Options::values] the values are given for lines by its offsets instead of
+    /// positions in request. Pairs are resolved when lines is requested and applied over
+    /// the values configured by [Options::values]. Unspecified lines stays unset.
+    ///
+    /// Available only for outputs
+    pub fn values_pairs(mut self, pairs: &[(LineId, bool)]) -> Self {
+        self.value_pairs.extend_from_slice(pairs);
+        self
+    }
 }
 
 /// GPIO chip interface info
+#[derive(Clone)]
 pub struct ChipInfo {
     name: String,
     label: String,
     num_lines: LineId,
+    consumer_prefix: Option<String>,
+    requested: LineRegistry,
+    line_names: Arc<OnceLock<LineNames>>,
+}
+
+/// Names of chip lines
+///
+/// Shared between clones of chip info, so lines are scanned once per opened chip.
+struct LineNames {
+    /// Offsets by names (the first line when names are duplicated)
+    offsets: HashMap<String, LineId>,
+    /// Names by offsets (empty for unnamed lines)
+    names: Vec<String>,
 }
 
 impl fmt::Display for ChipInfo {
@@ -328,12 +1420,58 @@ impl fmt::Display for ChipInfo {
     }
 }
 
+fn chip_number(name: &str) -> Option<u32> {
+    let prefix = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    name[prefix.len()..].parse().ok()
+}
+
+/// Select single chip with given label
+///
+/// Fails when no chips or more than one chip has such label.
+pub fn select_chip_by_label<T: core::ops::Deref<Target = ChipInfo>>(
+    chips: impl IntoIterator<Item = T>,
+    label: &str,
+) -> Result<T> {
+    let mut chips = chips.into_iter().filter(|chip| chip.label == label);
+
+    let chip = chips.next().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("GPIO chip with label '{}' not found", label),
+        )
+    })?;
+
+    if chips.next().is_some() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Multiple GPIO chips with label '{}' found", label),
+        )
+        .into());
+    }
+
+    Ok(chip)
+}
+
+/// Sort chips by number in descending order
+///
+/// Chips which names does not end with number are placed last.
+pub fn sort_chips<T: core::ops::Deref<Target = ChipInfo>>(chips: &mut [T]) {
+    chips.sort_by_key(|chip| core::cmp::Reverse(chip.number()));
+}
+
 impl ChipInfo {
     /// Get chip name
     pub fn name(&self) -> &str {
         &self.name
     }
 
+    /// Get chip number
+    ///
+    /// The number is parsed from the end of chip name (ex. `3` for `gpiochip3`).
+    pub fn number(&self) -> Option<u32> {
+        chip_number(&self.name)
+    }
+
     /// Get chip label
     pub fn label(&self) -> &str {
         &self.label
@@ -343,9 +1481,39 @@ impl ChipInfo {
     pub fn num_lines(&self) -> LineId {
         self.num_lines
     }
+
+    /// Get offsets of lines which currently requested via this chip by this process
+    ///
+    /// The lines are tracked until the corresponding requests are dropped.
+    /// The clones of chip shares the tracked lines.
+    pub fn my_requested_lines(&self) -> Vec<LineId> {
+        self.requested.lines()
+    }
+
+    /// Get the version of GPIO ABI which is used to interface with chip
+    pub fn abi(&self) -> AbiVersion {
+        abi_version()
+    }
+
+    /// Get prefix which prepended to consumer of each request
+    pub fn consumer_prefix(&self) -> Option<&str> {
+        self.consumer_prefix.as_deref()
+    }
 }
 
 impl Internal<ChipInfo> {
+    /// Create info of chip which is not backed by device (ex. simulated chip)
+    pub fn new(name: impl Into<String>, label: impl Into<String>, num_lines: LineId) -> Self {
+        Self(ChipInfo {
+            name: name.into(),
+            label: label.into(),
+            num_lines,
+            consumer_prefix: None,
+            requested: Default::default(),
+            line_names: Default::default(),
+        })
+    }
+
     pub fn from_fd(fd: RawFd) -> Result<Self> {
         let mut info = raw::GpioChipInfo::default();
 
@@ -355,9 +1523,160 @@ impl Internal<ChipInfo> {
             name: safe_get_str(&info.name)?.into(),
             label: safe_get_str(&info.label)?.into(),
             num_lines: info.lines,
+            consumer_prefix: None,
+            requested: Default::default(),
+            line_names: Default::default(),
         }))
     }
 
+    /// Probe the version of GPIO ABI supported by kernel
+    ///
+    /// Unlike [ChipInfo::abi] which reports ABI selected at compile time, the kernel is asked
+    /// using line info ioctl of ABI v2 (for line 0). When kernel supports both ABIs v2 is
+    /// reported. Older kernels cannot be distinguished using chip without lines, so it fails
+    /// in that case unless kernel rejects ioctl as unknown.
+    pub fn kernel_abi(&self, fd: RawFd) -> Result<AbiVersion> {
+        let mut info = raw::GpioV2LineInfoProbe::default();
+
+        probe_abi(
+            unsafe { raw::gpio_v2_get_line_info_probe(fd, &mut info) },
+            self.num_lines > 0,
+        )
+    }
+
+    /// Find line offset by name
+    pub fn find_line(&self, fd: RawFd, name: &str) -> Result<Option<LineId>> {
+        Ok(self.line_names_map(fd)?.offsets.get(name).copied())
+    }
+
+    /// Find line offsets by names
+    ///
+    /// The results are in order of names.
+    pub fn find_lines_by_name(
+        &self,
+        fd: RawFd,
+        names: &[impl AsRef<str>],
+    ) -> Result<Vec<Option<LineId>>> {
+        let known = &self.line_names_map(fd)?.offsets;
+
+        Ok(names
+            .iter()
+            .map(|name| known.get(name.as_ref()).copied())
+            .collect())
+    }
+
+    /// Find lines which names matches pattern
+    ///
+    /// Returns offsets and names of matched lines ordered by offsets.
+    pub fn find_lines(
+        &self,
+        fd: RawFd,
+        pattern: &str,
+        mode: NameMatch,
+    ) -> Result<Vec<(LineId, String)>> {
+        let mut lines = self
+            .line_names_map(fd)?
+            .offsets
+            .iter()
+            .filter(|(name, _)| mode.matches(pattern, name))
+            .map(|(name, &line)| (line, name.clone()))
+            .collect::<Vec<_>>();
+
+        lines.sort_unstable();
+
+        Ok(lines)
+    }
+
+    /// Get names of lines
+    ///
+    /// The names are in order of given lines (unnamed lines has empty names).
+    /// Names of all lines are read on first call and cached, since it does not change.
+    pub fn line_names(&self, fd: RawFd, lines: &[LineId]) -> Result<Vec<String>> {
+        let names = &self.line_names_map(fd)?.names;
+
+        lines
+            .iter()
+            .map(|&line| {
+                names
+                    .get(line as usize)
+                    .cloned()
+                    .ok_or_else(|| invalid_input("Line offset out of range"))
+            })
+            .collect()
+    }
+
+    /// Get line names to offsets mapping
+    ///
+    /// It will be scanned once and cached since line names does not change.
+    fn line_names_map(&self, fd: RawFd) -> Result<&LineNames> {
+        if let Some(names) = self.line_names.get() {
+            return Ok(names);
+        }
+
+        let mut offsets = HashMap::new();
+        let mut names = Vec::with_capacity(self.num_lines as _);
+
+        for line in 0..self.num_lines {
+            let info = self.line_info(fd, line)?;
+
+            if !info.name.is_empty() {
+                offsets.entry(info.name.clone()).or_insert(line);
+            }
+
+            names.push(info.name);
+        }
+
+        Ok(self.line_names.get_or_init(|| LineNames { offsets, names }))
+    }
+
+    /// Resolve line names to offsets
+    fn resolve_lines(&self, fd: RawFd, names: &[String]) -> Result<Vec<LineId>> {
+        let known = &self.line_names_map(fd)?.offsets;
+
+        names
+            .iter()
+            .map(|name| {
+                known.get(name).copied().ok_or_else(|| {
+                    let mut available = known.iter().collect::<Vec<_>>();
+                    available.sort_by_key(|(_, line)| **line);
+
+                    let available = available
+                        .into_iter()
+                        .map(|(name, _)| name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+
+                    Error::LineNotFound(format!("{:?} (available: {})", name, available))
+                })
+            })
+            .collect()
+    }
+
+    /// Set prefix which prepended to consumer of each request
+    pub fn set_consumer_prefix(&mut self, prefix: impl Into<String>) {
+        self.0.consumer_prefix = Some(prefix.into());
+    }
+
+    /// Prepend consumer prefix and truncate result to fit into kernel limit
+    fn prefixed_consumer(&self, consumer: &str) -> String {
+        let prefix = match &self.consumer_prefix {
+            Some(prefix) => prefix,
+            None => return consumer.into(),
+        };
+
+        let mut consumer = format!("{}{}", prefix, consumer);
+
+        // Keep space for terminating zero
+        let mut len = consumer.len().min(raw::GPIO_MAX_NAME_SIZE - 1);
+
+        while !consumer.is_char_boundary(len) {
+            len -= 1;
+        }
+
+        consumer.truncate(len);
+        consumer
+    }
+
     /// Request the info of a specific GPIO line.
     pub fn line_info(&self, fd: RawFd, line: LineId) -> Result<LineInfo> {
         #[cfg(not(feature = "v2"))]
@@ -378,66 +1697,408 @@ impl Internal<ChipInfo> {
 
             info.offset = line;
 
-            unsafe_call!(raw::v2::gpio_get_line_info(fd, &mut info))?;
+            unsafe_call!(raw::v2::gpio_get_line_info(fd, &mut info)).map_err(check_abi)?;
 
             info.as_info()
         }
     }
 
-    /// Request the GPIO chip to configure the lines passed as argument as outputs
+    /// Request the info of specific GPIO lines
+    pub fn lines_info(&self, fd: RawFd, lines: &[LineId]) -> Result<Vec<LineInfo>> {
+        lines.iter().map(|line| self.line_info(fd, *line)).collect()
+    }
+
+    /// Request the info of all GPIO lines
     ///
-    /// Calling this operation is a precondition to being able to set the state of the GPIO lines.
-    /// All the lines passed in one request must share the output mode and the active state.
-    pub fn request_lines<Direction: DirectionType>(
-        &self,
-        fd: RawFd,
-        options: Options<Direction, impl AsRef<[LineId]>, impl AsRef<str>>,
-    ) -> Result<(Internal<ValuesInfo>, RawFd)> {
-        let Options {
-            lines,
-            direction: _,
-            active,
-            edge,
-            bias,
-            drive,
-            values,
-            consumer,
-        } = options;
+    /// Fails when info of any line cannot be read.
+    pub fn line_info_all(&self, fd: RawFd) -> Result<Vec<LineInfo>> {
+        (0..self.num_lines)
+            .map(|line| self.line_info(fd, line))
+            .collect()
+    }
 
-        let direction = Direction::DIR;
-        let lines = lines.as_ref();
-        let consumer = consumer.as_ref();
+    /// Request the info of all GPIO lines keeping errors per line
+    ///
+    /// Unlike [Internal::<ChipInfo>::line_info_all] it does not stop on first failure.
+    pub fn line_info_all_lenient(&self, fd: RawFd) -> Vec<Result<LineInfo>> {
+        (0..self.num_lines)
+            .map(|line| self.line_info(fd, line))
+            .collect()
+    }
 
+    /// Start watching the info changes of a specific GPIO line
+    ///
+    /// Returns current line info. Changes can be read from chip fd as [RawInfoChange].
+    pub fn watch_line_info(&self, fd: RawFd, line: LineId) -> Result<LineInfo> {
         #[cfg(not(feature = "v2"))]
-        let fd = {
-            let mut request =
-                raw::v1::GpioHandleRequest::new(lines, direction, active, bias, drive, consumer)?;
+        {
+            let mut info = raw::v1::GpioLineInfo {
+                line_offset: line,
+                ..Default::default()
+            };
 
-            // TODO: edge detection
+            unsafe_call!(raw::v1::gpio_get_line_info_watch(fd, &mut info))?;
 
-            unsafe_call!(raw::v1::gpio_get_line_handle(fd, &mut request))?;
+            info.as_info()
+        }
 
-            if let Some(values) = values {
-                let mut data = raw::v1::GpioHandleData::from_values(lines.len(), &values);
+        #[cfg(feature = "v2")]
+        {
+            let mut info = raw::v2::GpioLineInfo::default();
 
-                unsafe_call!(raw::v1::gpio_set_line_values(fd, &mut data))?;
+            info.offset = line;
+
+            unsafe_call!(raw::v2::gpio_get_line_info_watch(fd, &mut info)).map_err(check_abi)?;
+
+            info.as_info()
+        }
+    }
+
+    /// Stop watching the info changes of a specific GPIO line
+    pub fn unwatch_line_info(&self, fd: RawFd, line: LineId) -> Result<()> {
+        let mut line = line;
+
+        unsafe_call!(raw::gpio_line_info_unwatch(fd, &mut line))?;
+
+        Ok(())
+    }
+
+    /// Probe features supported by GPIO line
+    ///
+    /// Features which already configured are reported by line info. Other features of unused
+    /// input lines are probed by requesting line with corresponding options (the request is
+    /// released immediately). Output lines is never probed because requesting it would change
+    /// its value.
+    ///
+    /// Many controllers keep the bias applied after the request released, so bias is probed
+    /// only when line reports pull-up or pull-down, and the last probe restores it. Otherwise
+    /// the original bias cannot be restored (kernel does not report whether bias is disabled
+    /// or left as is), so bias support is reported as unknown.
+    ///
+    /// Drive modes is never probed because it applies to outputs only and requesting line
+    /// as output would drive it, so drive modes are known only when already configured.
+    pub fn capabilities(&self, fd: RawFd, line: LineId) -> Result<LineCapabilities> {
+        let info = self.line_info(fd, line)?;
+
+        if info.used {
+            return Err(invalid_input("Line is used"));
+        }
+
+        let mut caps = LineCapabilities::default();
+
+        if !matches!(info.edge, EdgeDetect::Disable) {
+            caps.edge_detection = Some(true);
+        }
+        match info.bias {
+            Bias::PullUp => caps.pull_up = Some(true),
+            Bias::PullDown => caps.pull_down = Some(true),
+            Bias::Disable => (),
+        }
+        match info.drive {
+            Drive::OpenDrain => caps.open_drain = Some(true),
+            Drive::OpenSource => caps.open_source = Some(true),
+            Drive::PushPull => (),
+        }
+
+        if matches!(info.direction, Direction::Input) {
+            let probe = |options: Options<Input, [LineId; 1], &str>| {
+                self.probe(fd, options.active(info.active).consumer("probe"))
+            };
+
+            caps.edge_detection = Some(probe(Options::input([line]).edge(EdgeDetect::Both))?);
+
+            if !matches!(info.bias, Bias::Disable) {
+                let mut result = Ok(());
+
+                for bias in [Bias::PullUp, Bias::PullDown, Bias::Disable] {
+                    if bias == info.bias {
+                        continue;
+                    }
+                    match probe(Options::input([line]).bias(bias)) {
+                        Ok(supported) => *caps.bias_mut(bias) = Some(supported),
+                        Err(error) => {
+                            result = Err(error);
+                            break;
+                        }
+                    }
+                }
+
+                // Restore original bias even when probing failed
+                probe(Options::input([line]).bias(info.bias))?;
+
+                result?;
             }
+        }
 
-            request.fd
+        Ok(caps)
+    }
+
+    /// Try to request lines and release it immediately
+    ///
+    /// Returns `false` when options rejected as unsupported.
+    fn probe<Direction: DirectionType>(
+        &self,
+        fd: RawFd,
+        options: Options<Direction, impl AsRef<[LineId]>, impl AsRef<str>>,
+    ) -> Result<bool> {
+        use nix::errno::Errno;
+        use std::os::unix::io::FromRawFd;
+
+        // Kernel-internal ENOTSUPP which may leak from drivers
+        const ENOTSUPP: i32 = 524;
+
+        match self.request_lines(fd, options) {
+            Ok((_, fd)) => {
+                // Close request fd to release lines
+                drop(unsafe { std::fs::File::from_raw_fd(fd) });
+                Ok(true)
+            }
+            Err(error)
+                if matches!(
+                    error.raw_os_error(),
+                    Some(code) if code == Errno::EINVAL as i32
+                        || code == Errno::EOPNOTSUPP as i32
+                        || code == ENOTSUPP
+                ) =>
+            {
+                Ok(false)
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Request the GPIO chip to configure the lines passed as argument as outputs
+    ///
+    /// Calling this operation is a precondition to being able to set the state of the GPIO lines.
+    /// All the lines passed in one request must share the output mode and the active state.
+    pub fn request_lines<Direction: DirectionType>(
+        &self,
+        fd: RawFd,
+        options: Options<Direction, impl AsRef<[LineId]>, impl AsRef<str>>,
+    ) -> Result<(Internal<ValuesInfo>, RawFd)> {
+        options.validate()?;
+        check_consumer(options.consumer.as_ref())?;
+
+        if matches!(Direction::DIR, crate::Direction::Output) && !is_writable(fd)? {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "Chip is opened read-only, outputs cannot be requested",
+            )
+            .into());
+        }
+
+        let consumer = self.prefixed_consumer(options.consumer.as_ref());
+        let options = options.consumer(consumer);
+
+        let named_lines;
+        let lines = if options.names.is_empty() {
+            options.lines.as_ref()
+        } else {
+            named_lines = self.resolve_lines(fd, &options.names)?;
+            &named_lines[..]
+        };
+
+        let mut info = Internal::<ValuesInfo>::with_lines(&self.name, lines, &options)?;
+
+        // Keep chip to query requested lines later without re-opening it
+        info.0.chip = Some(LinesChip {
+            info: self.clone(),
+            file: unsafe { BorrowedFd::borrow_raw(fd) }
+                .try_clone_to_owned()?
+                .into(),
+        });
+
+        let Options {
+            lines: _,
+            direction: _,
+            active,
+            edge,
+            bias,
+            drive,
+            overrides,
+            event_buffer,
+            event_clock,
+            debounce,
+            consumer,
+            ..
+        } = options;
+
+        let values = info.values;
+
+        let direction = Direction::DIR;
+        let consumer = consumer.as_ref();
+
+        #[cfg(not(feature = "v2"))]
+        let fd = {
+            if !overrides.is_empty() {
+                return Err(invalid_input("Per-line options not supported"));
+            }
+
+            if debounce.is_some() {
+                return Err(invalid_input("Debounce not supported"));
+            }
+
+            if event_buffer.is_some() {
+                return Err(invalid_input("Event buffer size not supported"));
+            }
+
+            if matches!(event_clock, EventClock::Realtime) {
+                return Err(invalid_input("Event clock not supported"));
+            }
+
+            match edge {
+                Some(edge) if !matches!(edge, EdgeDetect::Disable) => {
+                    use nix::sys::epoll::{
+                        epoll_create1, epoll_ctl, EpollCreateFlags, EpollEvent, EpollFlags, EpollOp,
+                    };
+                    use std::os::unix::io::{FromRawFd, IntoRawFd};
+
+                    // ABI v1 has separate event fd for each line
+                    // so we multiplex it using epoll
+                    let epoll = epoll_create1(EpollCreateFlags::EPOLL_CLOEXEC)?;
+                    let epoll_file = unsafe { std::fs::File::from_raw_fd(epoll) };
+
+                    for (id, &line) in lines.iter().enumerate() {
+                        let mut request =
+                            raw::v1::GpioEventRequest::new(line, active, edge, bias, consumer)?;
+
+                        unsafe_call!(raw::v1::gpio_get_line_event(fd, &mut request))?;
+
+                        let event_file = unsafe { std::fs::File::from_raw_fd(request.fd) };
+
+                        set_nonblock(request.fd)?;
+
+                        epoll_ctl(
+                            epoll,
+                            EpollOp::EpollCtlAdd,
+                            request.fd,
+                            &mut EpollEvent::new(EpollFlags::EPOLLIN, id as _),
+                        )?;
+
+                        info.0.events.push(event_file);
+                    }
+
+                    epoll_file.into_raw_fd()
+                }
+                _ => {
+                    let mut request = raw::v1::GpioHandleRequest::new(
+                        lines, direction, active, bias, drive, values, consumer,
+                    )?;
+
+                    unsafe_call!(raw::v1::gpio_get_line_handle(fd, &mut request))?;
+
+                    request.fd
+                }
+            }
         };
 
         #[cfg(feature = "v2")]
         let fd = {
             let mut request = raw::v2::GpioLineRequest::new(
-                lines, direction, active, edge, bias, drive, values, consumer,
+                lines,
+                direction,
+                active,
+                edge,
+                bias,
+                drive,
+                debounce,
+                event_clock,
+                values,
+                &overrides,
+                consumer,
             )?;
 
-            unsafe_call!(raw::v2::gpio_get_line(fd, &mut request))?;
+            request.event_buffer_size = event_buffer.unwrap_or(0);
+
+            unsafe_call!(raw::v2::gpio_get_line(fd, &mut request)).map_err(check_abi)?;
 
             request.fd
         };
 
-        Ok((Internal::<ValuesInfo>::new(&self.name, consumer, lines), fd))
+        info.0.registration = Some(self.requested.register(lines));
+
+        Ok((info, fd))
+    }
+}
+
+/// Guard which writes values to lines on drop
+///
+/// Errors on drop are ignored, use [RestoreValues::restore] to get it.
+pub struct RestoreValues<'a> {
+    info: &'a Internal<ValuesInfo>,
+    fd: RawFd,
+    values: Option<Values>,
+}
+
+impl RestoreValues<'_> {
+    /// Values which will be written
+    pub fn values(&self) -> Values {
+        self.values.unwrap_or_default()
+    }
+
+    /// Write values now
+    pub fn restore(mut self) -> Result<()> {
+        match self.values.take() {
+            Some(values) => self.info.set_values(self.fd, values),
+            None => Ok(()),
+        }
+    }
+
+    /// Do not write values on drop
+    pub fn disarm(mut self) {
+        self.values = None;
+    }
+}
+
+impl Drop for RestoreValues<'_> {
+    fn drop(&mut self) {
+        if let Some(values) = self.values.take() {
+            let _ = self.info.set_values(self.fd, values);
+        }
+    }
+}
+
+/// Registry of lines which requested by this process
+#[derive(Debug, Clone, Default)]
+struct LineRegistry(Arc<Mutex<Vec<LineId>>>);
+
+impl LineRegistry {
+    fn register(&self, lines: &[LineId]) -> LineRegistration {
+        self.lock().extend_from_slice(lines);
+
+        LineRegistration {
+            registry: self.clone(),
+            lines: lines.to_owned(),
+        }
+    }
+
+    fn lines(&self) -> Vec<LineId> {
+        let mut lines = self.lock().clone();
+        lines.sort_unstable();
+        lines
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<LineId>> {
+        // The list is always consistent so poisoning can be ignored
+        self.0.lock().unwrap_or_else(|error| error.into_inner())
+    }
+}
+
+/// Registration of requested lines which unregisters lines on drop
+struct LineRegistration {
+    registry: LineRegistry,
+    lines: Vec<LineId>,
+}
+
+impl Drop for LineRegistration {
+    fn drop(&mut self) {
+        let mut lines = self.registry.lock();
+
+        for line in &self.lines {
+            if let Some(index) = lines.iter().position(|requested| requested == line) {
+                lines.swap_remove(index);
+            }
+        }
     }
 }
 
@@ -463,4 +2124,798 @@ mod test {
             .drive(Drive::OpenDrain)
             .values([true, false]);
     }
+
+    #[test]
+    fn options_from_info() {
+        let info = Internal::<ValuesInfo>::new(
+            "gpiochip0",
+            &Options::output([11, 2])
+                .bias(Bias::PullUp)
+                .active(Active::Low)
+                .consumer("gpout")
+                .drive(Drive::OpenDrain)
+                .values([true, false]),
+        )
+        .unwrap();
+
+        let options = info.options::<Output>();
+
+        assert_eq!(options.lines, [11, 2]);
+        assert_eq!(options.consumer, "gpout");
+        assert_eq!(options.active, Active::Low);
+        assert_eq!(options.edge, None);
+        assert_eq!(options.bias, Some(Bias::PullUp));
+        assert_eq!(options.drive, Some(Drive::OpenDrain));
+        assert_eq!(
+            options.values,
+            Some(Values {
+                bits: 0b01,
+                mask: 0b11
+            })
+        );
+    }
+
+    #[test]
+    fn request_config_from_info() {
+        let info = Internal::<ValuesInfo>::new(
+            "gpiochip1",
+            &Options::input([3, 7])
+                .consumer("gpin")
+                .active(Active::Low)
+                .edge(EdgeDetect::Both)
+                .bias(Bias::PullDown)
+                .event_buffer(16)
+                .event_clock(EventClock::Realtime),
+        )
+        .unwrap();
+
+        let config = info.request_config::<Input>();
+
+        assert_eq!(
+            config,
+            RequestConfig {
+                chip_name: "gpiochip1".into(),
+                consumer: "gpin".into(),
+                lines: vec![3, 7],
+                direction: Direction::Input,
+                active: Active::Low,
+                edge: Some(EdgeDetect::Both),
+                bias: Some(Bias::PullDown),
+                drive: None,
+                values: None,
+                event_buffer: Some(16),
+                event_clock: EventClock::Realtime,
+                debounce: None,
+            }
+        );
+    }
+
+    #[test]
+    fn check_line_edge() {
+        let options = Options::input([3, 7, 9])
+            .edge(EdgeDetect::Rising)
+            .line_edge([9], EdgeDetect::Both);
+        let info = Internal::<ValuesInfo>::new("gpiochip0", &options).unwrap();
+
+        assert!(info.check_edge(3, Edge::Rising).is_ok());
+        assert!(info.check_edge(3, Edge::Falling).is_err());
+        assert!(info.check_edge(9, Edge::Falling).is_ok());
+        assert!(info.check_edge(5, Edge::Rising).is_err());
+
+        let info = Internal::<ValuesInfo>::new("gpiochip0", &Options::input([3])).unwrap();
+        assert!(info.check_edge(3, Edge::Rising).is_err());
+    }
+
+    #[test]
+    fn toggle_values() {
+        let info = Internal::<ValuesInfo>::new("gpiochip0", &Options::output([4, 9])).unwrap();
+
+        // values of lines as kernel reports it
+        let initial: Values = "10".parse().unwrap();
+        let mut current = initial;
+
+        current = info.toggled_values(current, None).unwrap();
+        assert_eq!(current, "01".parse().unwrap());
+
+        current = info.toggled_values(current, None).unwrap();
+        assert_eq!(current, initial);
+
+        current = info.toggled_values(current, Some(9)).unwrap();
+        assert_eq!(current, "00".parse().unwrap());
+
+        current = info.toggled_values(current, Some(9)).unwrap();
+        assert_eq!(current, initial);
+
+        assert!(info.toggled_values(current, Some(5)).is_err());
+    }
+
+    #[test]
+    fn probe_abi_result() {
+        use nix::errno::Errno;
+
+        assert_eq!(probe_abi(Ok(0), true).unwrap(), AbiVersion::V2);
+        assert_eq!(probe_abi(Err(Errno::ENOTTY), true).unwrap(), AbiVersion::V1);
+        assert_eq!(probe_abi(Err(Errno::EINVAL), true).unwrap(), AbiVersion::V1);
+        assert_eq!(
+            probe_abi(Err(Errno::EBADF), true)
+                .err()
+                .unwrap()
+                .raw_os_error(),
+            Some(nix::libc::EBADF)
+        );
+
+        // no lines to probe
+        assert_eq!(probe_abi(Ok(0), false).unwrap(), AbiVersion::V2);
+        assert_eq!(
+            probe_abi(Err(Errno::ENOTTY), false).unwrap(),
+            AbiVersion::V1
+        );
+        assert_eq!(
+            probe_abi(Err(Errno::EINVAL), false).err().unwrap().kind(),
+            io::ErrorKind::Unsupported
+        );
+
+        let chip = Internal(ChipInfo {
+            name: "gpiochip0".into(),
+            label: "gpio-mock".into(),
+            num_lines: 8,
+            consumer_prefix: None,
+            requested: Default::default(),
+            line_names: Default::default(),
+        });
+        assert!(chip.kernel_abi(-1).is_err());
+    }
+
+    #[cfg(feature = "v2")]
+    #[test]
+    fn check_abi_hint() {
+        let error = check_abi(nix::errno::Errno::ENOTTY.into());
+        assert_eq!(error.kind(), io::ErrorKind::Unsupported);
+        assert!(error.to_string().contains("without `v2` feature"));
+
+        let error = check_abi(nix::errno::Errno::EBUSY.into());
+        assert_eq!(error.raw_os_error(), Some(nix::libc::EBUSY));
+    }
+
+    #[test]
+    fn single_value_unknown_line() {
+        let info = Internal::<ValuesInfo>::new("gpiochip0", &Options::output([4, 9])).unwrap();
+
+        let error = info.get_value(-1, 5).err().unwrap();
+        assert!(matches!(error, Error::LineNotFound(ref line) if line == "5"));
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+
+        let error = info.set_value(-1, 10, true).err().unwrap();
+        assert!(matches!(error, Error::LineNotFound(ref line) if line == "10"));
+
+        let error = info.check_edge(7, Edge::Rising).err().unwrap();
+        assert!(matches!(error, Error::LineNotFound(_)));
+
+        let options = Options::output([4, 9]).values_pairs(&[(5, true)]);
+        let error = Internal::<ValuesInfo>::new("gpiochip0", &options)
+            .err()
+            .unwrap();
+        assert!(matches!(error, Error::LineNotFound(_)));
+
+        // known line reaches the ioctl which fails on invalid fd
+        assert_eq!(
+            info.set_value(-1, 9, true).err().unwrap().raw_os_error(),
+            Some(nix::libc::EBADF)
+        );
+    }
+
+    #[test]
+    fn restore_guard() {
+        use std::os::unix::io::AsRawFd;
+
+        let info = Internal::<ValuesInfo>::new("gpiochip0", &Options::output([4, 9])).unwrap();
+        let (reader, _writer) = nix::unistd::pipe().unwrap();
+
+        let guard = info
+            .restore_guard(reader.as_raw_fd(), [true, false].convert())
+            .unwrap();
+        assert_eq!(guard.values(), "10".parse().unwrap());
+        // pipe does not support GPIO ioctls
+        assert!(guard.restore().is_err());
+
+        let guard = info
+            .restore_guard(reader.as_raw_fd(), [Some(true), None].convert())
+            .unwrap();
+        assert_eq!(guard.values(), "x0".parse().unwrap());
+        guard.disarm();
+    }
+
+    #[test]
+    fn iter_values() {
+        let info = Internal::<ValuesInfo>::new("gpiochip0", &Options::input([27, 3, 11])).unwrap();
+
+        let values: Masked<u8> = "1x1".parse().unwrap();
+
+        assert_eq!(
+            info.iter_values(&values).collect::<Vec<_>>(),
+            [(27, Some(true)), (3, None), (11, Some(true))]
+        );
+    }
+
+    #[test]
+    fn format_values() {
+        let info = Internal::<ValuesInfo>::new("gpiochip0", &Options::input([3, 7, 1])).unwrap();
+
+        let names = ["status-led".into(), "".into(), "button".into()];
+
+        assert_eq!(
+            info.format_values([Some(true), Some(false), None], &names),
+            "status-led=1 7=0 button=x"
+        );
+
+        assert_eq!(info.format_values(0b110u8, &[]), "3=0 7=1 1=1");
+    }
+
+    #[test]
+    fn values_pairs() {
+        let info = Internal::<ValuesInfo>::new(
+            "gpiochip0",
+            &Options::output([11, 2, 7]).values_pairs(&[(7, true), (11, false)]),
+        )
+        .unwrap();
+
+        assert_eq!(
+            info.values(),
+            Some(Values {
+                bits: 0b100,
+                mask: 0b101
+            })
+        );
+
+        let info = Internal::<ValuesInfo>::new(
+            "gpiochip0",
+            &Options::output([11, 2, 7])
+                .values([true, true, false])
+                .values_pairs(&[(2, false), (7, true)]),
+        )
+        .unwrap();
+
+        assert_eq!(
+            info.values(),
+            Some(Values {
+                bits: 0b101,
+                mask: 0b111
+            })
+        );
+
+        assert!(Internal::<ValuesInfo>::new(
+            "gpiochip0",
+            &Options::output([11, 2, 7]).values_pairs(&[(3, true)]),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn chip_by_label() {
+        let chip = |name: &str, label: &str| {
+            Box::new(ChipInfo {
+                name: name.into(),
+                label: label.into(),
+                num_lines: 8,
+                consumer_prefix: None,
+                requested: Default::default(),
+                line_names: Default::default(),
+            })
+        };
+
+        let chips = [
+            chip("gpiochip0", "pinctrl-bcm2711"),
+            chip("gpiochip1", "raspberrypi-exp-gpio"),
+        ];
+
+        let found = select_chip_by_label(chips.clone(), "raspberrypi-exp-gpio").unwrap();
+        assert_eq!(found.name(), "gpiochip1");
+
+        let found = select_chip_by_label(chips.clone(), "pinctrl-bcm2711").unwrap();
+        assert_eq!(found.name(), "gpiochip0");
+
+        let error = select_chip_by_label(chips, "unknown").err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+
+        let chips = [chip("gpiochip0", "gpio-sim"), chip("gpiochip1", "gpio-sim")];
+
+        let error = select_chip_by_label(chips, "gpio-sim").err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn sort_chips_desc() {
+        let chip = |name: &str| {
+            Box::new(ChipInfo {
+                name: name.into(),
+                label: "gpio-mock".into(),
+                num_lines: 8,
+                consumer_prefix: None,
+                requested: Default::default(),
+                line_names: Default::default(),
+            })
+        };
+
+        let mut chips = [
+            chip("gpiochip2"),
+            chip("gpio-sim"),
+            chip("gpiochip10"),
+            chip("gpiochip0"),
+        ];
+
+        sort_chips(&mut chips);
+
+        assert_eq!(
+            chips.iter().map(|chip| chip.name()).collect::<Vec<_>>(),
+            ["gpiochip10", "gpiochip2", "gpiochip0", "gpio-sim"]
+        );
+    }
+
+    #[test]
+    fn consumer_too_long() {
+        assert!(check_consumer("gpio-consumer").is_ok());
+        assert!(check_consumer(&"c".repeat(MAX_CONSUMER_LEN)).is_ok());
+
+        let error = check_consumer(&"c".repeat(40)).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(
+            error.to_string(),
+            "Consumer string too long (40 bytes, max 31 bytes)"
+        );
+    }
+
+    #[test]
+    fn request_outputs_read_only() {
+        use std::{fs::File, os::unix::io::AsRawFd};
+
+        let chip = Internal(ChipInfo {
+            name: "gpiochip0".into(),
+            label: "test".into(),
+            num_lines: 8,
+            consumer_prefix: None,
+            requested: Default::default(),
+            line_names: Default::default(),
+        });
+
+        let file = File::open("/dev/null").unwrap();
+        assert!(!is_writable(file.as_raw_fd()).unwrap());
+
+        let error = chip
+            .request_lines(file.as_raw_fd(), Options::output([1]))
+            .err()
+            .unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::PermissionDenied);
+
+        let file = std::fs::OpenOptions::new()
+            .write(true)
+            .open("/dev/null")
+            .unwrap();
+        assert!(is_writable(file.as_raw_fd()).unwrap());
+    }
+
+    #[test]
+    fn lines_info_without_chip() {
+        let info = Internal::<ValuesInfo>::new("gpiochip0", &Options::input([2, 1, 0])).unwrap();
+
+        let error = info.lines_info().err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn lines_names_from_chip() {
+        let chip = Internal(ChipInfo {
+            name: "gpiochip0".into(),
+            label: "test".into(),
+            num_lines: 3,
+            consumer_prefix: None,
+            requested: Default::default(),
+            line_names: Default::default(),
+        });
+
+        // pre-fill cache to avoid reading info of lines
+        let names = ["led", "", "button"].map(String::from).to_vec();
+        let offsets = HashMap::from([("led".into(), 0), ("button".into(), 2)]);
+        assert!(chip.line_names.set(LineNames { offsets, names }).is_ok());
+
+        // clones shares the cache
+        let clone = chip.clone();
+        assert_eq!(clone.find_line(-1, "button").unwrap(), Some(2));
+
+        let mut info =
+            Internal::<ValuesInfo>::new("gpiochip0", &Options::input([2, 1, 0])).unwrap();
+
+        let error = info.line_names().err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+
+        info.0.chip = Some(LinesChip {
+            info: clone,
+            file: std::fs::File::open("/dev/null").unwrap(),
+        });
+
+        assert_eq!(info.line_names().unwrap(), ["button", "", "led"]);
+        assert!(chip.line_names(-1, &[3]).is_err());
+    }
+
+    #[test]
+    fn consumer_prefix() {
+        let mut chip = Internal(ChipInfo {
+            name: "gpiochip0".into(),
+            label: "test".into(),
+            num_lines: 8,
+            consumer_prefix: None,
+            requested: Default::default(),
+            line_names: Default::default(),
+        });
+
+        assert_eq!(chip.prefixed_consumer("gpin"), "gpin");
+
+        chip.set_consumer_prefix("mysvc:");
+
+        assert_eq!(chip.prefixed_consumer("gpin"), "mysvc:gpin");
+        assert_eq!(
+            chip.prefixed_consumer("very-long-consumer-name-of-subsystem"),
+            "mysvc:very-long-consumer-name-o"
+        );
+
+        chip.set_consumer_prefix("ä".repeat(16));
+
+        assert_eq!(chip.prefixed_consumer(""), "ä".repeat(15));
+    }
+
+    #[test]
+    fn named_options() {
+        let options = Options::output_named(["led", "buzzer"])
+            .values([true, false])
+            .consumer("gpout")
+            .to_owned();
+
+        assert!(options.lines.is_empty());
+        assert_eq!(options.names, ["led", "buzzer"]);
+
+        let info = Internal::<ValuesInfo>::with_lines("gpiochip0", &[5, 9], &options).unwrap();
+
+        assert_eq!(info.lines(), [5, 9]);
+        assert!(info.options::<Output>().names.is_empty());
+    }
+
+    #[test]
+    fn truncate_values() {
+        let info = Internal::<ValuesInfo>::new("gpiochip0", &Options::input([3, 7, 1])).unwrap();
+
+        let mut values = Values::from(Bits::MAX);
+
+        info.truncate_values(&mut values);
+
+        assert_eq!(values.mask, 0b111);
+        assert_eq!(values.bits, 0b111);
+    }
+
+    #[test]
+    fn replace_lines() {
+        let options = Options::input_named(["button"])
+            .bias(Bias::PullUp)
+            .consumer("gpin")
+            .lines([5]);
+
+        assert_eq!(options.lines, [5]);
+        assert!(options.names.is_empty());
+        assert_eq!(options.bias, Some(Bias::PullUp));
+        assert_eq!(options.consumer, "gpin");
+    }
+
+    #[test]
+    fn truncate_values_buffers() {
+        let info = Internal::<ValuesInfo>::new("gpiochip0", &Options::input([3, 7, 1])).unwrap();
+
+        let mut oversized = [true; 8];
+        info.truncate_values(&mut oversized);
+        assert_eq!(
+            oversized,
+            [true, true, true, false, false, false, false, false]
+        );
+
+        let mut oversized = [Some(true); 5];
+        info.truncate_values(&mut oversized);
+        assert_eq!(oversized, [Some(true), Some(true), Some(true), None, None]);
+
+        let mut undersized = [true; 2];
+        info.truncate_values(&mut undersized);
+        assert_eq!(undersized, [true; 2]);
+
+        let mut undersized = Masked::<u8>::from(0b11);
+        info.truncate_values(&mut undersized);
+        assert_eq!(
+            undersized,
+            Masked::<u8> {
+                bits: 0b11,
+                mask: 0b111
+            }
+        );
+    }
+
+    #[test]
+    fn line_registry() {
+        let registry = LineRegistry::default();
+
+        let first = registry.register(&[7, 3]);
+        let second = registry.clone().register(&[1]);
+
+        assert_eq!(registry.lines(), [1, 3, 7]);
+
+        drop(first);
+
+        assert_eq!(registry.lines(), [1]);
+
+        drop(second);
+
+        assert!(registry.lines().is_empty());
+    }
+
+    #[test]
+    fn truncate_initial_values() {
+        let info =
+            Internal::<ValuesInfo>::new("gpiochip0", &Options::output([0, 1, 2]).values(0xffu8))
+                .unwrap();
+
+        assert_eq!(
+            info.values,
+            Some(Values {
+                bits: 0b111,
+                mask: 0b111
+            })
+        );
+    }
+
+    #[test]
+    fn request_values_validated() {
+        use std::{fs::File, os::unix::io::AsRawFd};
+
+        let chip = Internal(ChipInfo {
+            name: "gpiochip0".into(),
+            label: "test".into(),
+            num_lines: 8,
+            consumer_prefix: None,
+            requested: Default::default(),
+            line_names: Default::default(),
+        });
+
+        let file = File::open("/dev/null").unwrap();
+
+        // rejected before accessing chip
+        let error = chip
+            .request_lines(file.as_raw_fd(), Options::output([0, 1]).values([true]))
+            .err()
+            .unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+
+        // wider values passes validation and fails on read-only chip only
+        let error = chip
+            .request_lines(file.as_raw_fd(), Options::output([0, 1, 2]).values(0xffu8))
+            .err()
+            .unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::PermissionDenied);
+    }
+
+    #[test]
+    fn reconfigure_lines_changed() {
+        let mut info = Internal::<ValuesInfo>::new("gpiochip0", &Options::input([3, 7])).unwrap();
+
+        assert!(info
+            .reconfigure(-1, &Options::input([3, 8]).bias(Bias::PullUp))
+            .is_err());
+        assert_eq!(info.bias, None);
+    }
+
+    #[cfg(feature = "v2")]
+    #[test]
+    fn event_buffer_split_event() {
+        let mut event = RawEvent::default();
+        event.offset = 7;
+        event.id = raw::v2::GPIO_LINE_EVENT_FALLING_EDGE;
+
+        let data = event.as_mut().to_vec();
+        let index = LineMap::new(&[3, 7]);
+        let mut buffer = EventBuffer::default();
+        let mut events = Vec::new();
+
+        let count = buffer
+            .decode(&data[..10], &index, EventClock::Monotonic, &mut events)
+            .unwrap();
+        assert_eq!(count, 0);
+        assert_eq!(buffer.read_len(1), EventBuffer::EVENT_SIZE);
+
+        let count = buffer
+            .decode(&data[10..], &index, EventClock::Monotonic, &mut events)
+            .unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(buffer.read_len(1), EventBuffer::EVENT_SIZE);
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].line, 1);
+        assert_eq!(events[0].offset, 7);
+        assert_eq!(events[0].edge, Edge::Falling);
+    }
+
+    #[cfg(feature = "v2")]
+    #[test]
+    fn event_buffer() {
+        use std::{
+            fs::File,
+            io::{Read, Write},
+            os::unix::io::FromRawFd,
+        };
+
+        let raw_event = |offset, seqno| {
+            let mut event = RawEvent::default();
+            event.offset = offset;
+            event.id = raw::v2::GPIO_LINE_EVENT_RISING_EDGE;
+            event.seqno = seqno;
+            event
+        };
+
+        let (reader, writer) = nix::unistd::pipe().unwrap();
+        let mut reader = unsafe { File::from_raw_fd(reader) };
+        let mut writer = unsafe { File::from_raw_fd(writer) };
+
+        let mut data = Vec::new();
+        for (offset, seqno) in [(3, 1), (7, 2), (3, 3)] {
+            data.extend_from_slice(raw_event(offset, seqno).as_mut());
+        }
+
+        // Split the second event between writes
+        let split = EventBuffer::EVENT_SIZE + 5;
+        writer.write_all(&data[..split]).unwrap();
+
+        let index = LineMap::new(&[3, 7]);
+        let mut buffer = EventBuffer::default();
+        let mut events = Vec::new();
+
+        let mut chunk = vec![0; buffer.read_len(3)];
+        let len = reader.read(&mut chunk).unwrap();
+        assert_eq!(
+            buffer
+                .decode(&chunk[..len], &index, EventClock::Monotonic, &mut events)
+                .unwrap(),
+            1
+        );
+        assert_eq!(buffer.read_len(3), 3 * EventBuffer::EVENT_SIZE);
+
+        writer.write_all(&data[split..]).unwrap();
+
+        let mut chunk = vec![0; buffer.read_len(3)];
+        let len = reader.read(&mut chunk).unwrap();
+        assert_eq!(
+            buffer
+                .decode(&chunk[..len], &index, EventClock::Monotonic, &mut events)
+                .unwrap(),
+            2
+        );
+
+        assert_eq!(
+            events
+                .iter()
+                .map(|event| (event.line, event.offset, event.seqno))
+                .collect::<Vec<_>>(),
+            [(0, 3, Some(1)), (1, 7, Some(2)), (0, 3, Some(3))]
+        );
+    }
+
+    #[test]
+    fn chip_number_from_name() {
+        assert_eq!(chip_number("gpiochip0"), Some(0));
+        assert_eq!(chip_number("gpiochip12"), Some(12));
+        assert_eq!(chip_number("gpiochip"), None);
+        assert_eq!(chip_number(""), None);
+    }
+
+    #[test]
+    fn validate_lines() {
+        let error = Options::input(Vec::<LineId>::new()).validate().unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+
+        assert!(Options::input(Vec::<LineId>::new())
+            .names(["led"])
+            .validate()
+            .is_ok());
+
+        let lines = (0..MAX_VALUES as LineId + 1).collect::<Vec<_>>();
+        let error = Options::input(&lines).validate().unwrap_err();
+        assert!(matches!(error, Error::TooManyLines));
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+
+        assert!(Options::input(&lines[..MAX_VALUES]).validate().is_ok());
+    }
+
+    #[test]
+    fn split_options() {
+        let lines = (0..80).collect::<Vec<LineId>>();
+        let options = Options::output(&lines)
+            .values([true, false])
+            .values_pairs(&[(70, true)])
+            .line_config(75, LineConfig::default().bias(Bias::PullUp));
+
+        let parts = options.split().unwrap();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].lines, &lines[..64]);
+        assert_eq!(parts[1].lines, &lines[64..]);
+        assert!(parts.iter().all(|part| part.values.is_none()));
+        assert_eq!(parts[0].value_pairs, [(0, true), (1, false)]);
+        assert_eq!(parts[1].value_pairs, [(70, true)]);
+        assert!(parts[0].overrides.is_empty());
+        assert_eq!(parts[1].overrides.len(), 1);
+        assert!(parts.iter().all(|part| part.validate().is_ok()));
+
+        assert_eq!(Options::input([3, 1]).split().unwrap().len(), 1);
+        assert!(Options::input(Vec::<LineId>::new())
+            .names(["led"])
+            .split()
+            .is_err());
+    }
+
+    #[test]
+    fn validate_values() {
+        assert!(Options::output([1, 2])
+            .values([true, false])
+            .validate()
+            .is_ok());
+        assert!(Options::output([1, 2]).values(0b10u8).validate().is_ok());
+
+        // values above lines are masked off
+        assert!(Options::output([1, 2])
+            .values([true, false, true])
+            .validate()
+            .is_ok());
+        assert!(Options::output([1, 2]).values(0xffu8).validate().is_ok());
+
+        let error = Options::output([1, 2])
+            .values([true])
+            .validate()
+            .unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+
+        let error = Options::output([1, 2])
+            .values([Some(true), None])
+            .validate()
+            .unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn validate_input_drive() {
+        assert!(Options::input([1, 2]).validate().is_ok());
+
+        let options =
+            Options::input([1, 2]).line_config(2, LineConfig::default().drive(Drive::OpenDrain));
+        assert_eq!(
+            options.validate().unwrap_err().kind(),
+            std::io::ErrorKind::InvalidInput
+        );
+
+        let mut options = Options::input([1, 2]);
+        options.drive = Some(Drive::OpenSource);
+        assert!(options.validate().is_err());
+    }
+
+    #[test]
+    fn validate_output_edge() {
+        assert!(Options::output([1, 2])
+            .line_config(1, LineConfig::default().edge(EdgeDetect::Disable))
+            .validate()
+            .is_ok());
+
+        let options =
+            Options::output([1, 2]).line_config(1, LineConfig::default().edge(EdgeDetect::Both));
+        assert_eq!(
+            options.validate().unwrap_err().kind(),
+            std::io::ErrorKind::InvalidInput
+        );
+
+        let mut options = Options::output([1, 2]);
+        options.debounce = Some(Time::from_millis(1));
+        assert!(options.validate().is_err());
+
+        let mut options = Options::output([1, 2]);
+        options.edge = Some(EdgeDetect::Rising);
+        assert!(options.validate().is_err());
+    }
 }
@@ -3,8 +3,8 @@ pub mod v1;
 #[cfg(feature = "v2")]
 pub mod v2;
 
-pub(self) const GPIO_MAX_NAME_SIZE: usize = 32;
-pub(self) const GPIO_MAGIC: u8 = 0xB4;
+pub const GPIO_MAX_NAME_SIZE: usize = 32;
+const GPIO_MAGIC: u8 = 0xB4;
 
 // All the structs used for ioctl must be represented in C otherwise weird memory mappings happen.
 //
@@ -19,8 +19,25 @@ pub struct GpioChipInfo {
     pub lines: u32,
 }
 
+// Line info of ABI v2 (for line 0 when zeroed) to probe kernel regardless of selected ABI
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct GpioV2LineInfoProbe([u8; 256]);
+
+impl Default for GpioV2LineInfoProbe {
+    fn default() -> Self {
+        Self([0; 256])
+    }
+}
+
 nix::ioctl_read!(gpio_get_chip_info, GPIO_MAGIC, 0x01, GpioChipInfo);
-nix::ioctl_read!(gpio_line_info_unwatch, GPIO_MAGIC, 0x0c, u32);
+nix::ioctl_readwrite!(gpio_line_info_unwatch, GPIO_MAGIC, 0x0c, u32);
+nix::ioctl_readwrite!(
+    gpio_v2_get_line_info_probe,
+    GPIO_MAGIC,
+    0x05,
+    GpioV2LineInfoProbe
+);
 
 #[cfg(test)]
 mod test {
@@ -31,5 +48,6 @@ mod test {
         use core::mem::size_of;
 
         assert_eq!(size_of::<GpioChipInfo>(), 68);
+        assert_eq!(size_of::<GpioV2LineInfoProbe>(), 256);
     }
 }
@@ -24,6 +24,11 @@ pub const GPIOEVENT_REQUEST_FALLING_EDGE: u32 = 1 << 1;
 pub const GPIOEVENT_REQUEST_BOTH_EDGES: u32 =
     GPIOEVENT_REQUEST_RISING_EDGE | GPIOEVENT_REQUEST_FALLING_EDGE;
 
+// Line changed reason
+pub const GPIOLINE_CHANGED_REQUESTED: u32 = 1;
+pub const GPIOLINE_CHANGED_RELEASED: u32 = 2;
+pub const GPIOLINE_CHANGED_CONFIG: u32 = 3;
+
 pub const GPIOEVENT_EVENT_RISING_EDGE: u32 = 0x01;
 pub const GPIOEVENT_EVENT_FALLING_EDGE: u32 = 0x02;
 
@@ -36,6 +41,21 @@ pub struct GpioLineInfo {
     pub consumer: [u8; GPIO_MAX_NAME_SIZE],
 }
 
+#[derive(Clone, Copy, Default)]
+#[repr(C)]
+pub struct GpioLineInfoChanged {
+    pub info: GpioLineInfo,
+    pub timestamp: u64,
+    pub event_type: u32,
+    padding: [u32; 5],
+}
+
+impl AsMut<[u8; core::mem::size_of::<GpioLineInfoChanged>()]> for GpioLineInfoChanged {
+    fn as_mut(&mut self) -> &mut [u8; core::mem::size_of::<GpioLineInfoChanged>()] {
+        unsafe { core::mem::transmute(self) }
+    }
+}
+
 pub const GPIOHANDLES_MAX: usize = 64;
 
 #[derive(Clone, Copy)]
@@ -134,6 +154,7 @@ mod test {
         use core::mem::size_of;
 
         assert_eq!(size_of::<GpioLineInfo>(), 72);
+        assert_eq!(size_of::<GpioLineInfoChanged>(), 104);
         assert_eq!(size_of::<GpioHandleRequest>(), 364);
         assert_eq!(size_of::<GpioEventRequest>(), 48);
         assert_eq!(size_of::<GpioHandleConfig>(), 84);
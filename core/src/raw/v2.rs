@@ -16,18 +16,18 @@ pub const GPIO_LINE_FLAG_OPEN_SOURCE: u64 = 1 << 7;
 pub const GPIO_LINE_FLAG_BIAS_PULL_UP: u64 = 1 << 8;
 pub const GPIO_LINE_FLAG_BIAS_PULL_DOWN: u64 = 1 << 9;
 pub const GPIO_LINE_FLAG_BIAS_DISABLED: u64 = 1 << 10;
-//pub const GPIO_LINE_FLAG_EVENT_CLOCK_REALTIME: u64 = 1 << 11;
+pub const GPIO_LINE_FLAG_EVENT_CLOCK_REALTIME: u64 = 1 << 11;
 //pub const GPIO_LINE_FLAG_EVENT_CLOCK_HTE: u64 = 1 << 12;
 
 // Line attr ids
-//pub const GPIO_LINE_ATTR_ID_FLAGS: u32 = 1;
+pub const GPIO_LINE_ATTR_ID_FLAGS: u32 = 1;
 pub const GPIO_LINE_ATTR_ID_OUTPUT_VALUES: u32 = 2;
-//pub const GPIO_LINE_ATTR_ID_DEBOUNCE: u32 = 3;
+pub const GPIO_LINE_ATTR_ID_DEBOUNCE: u32 = 3;
 
 // Line changed reason
-//pub const GPIO_LINE_CHANGED_REQUESTED: u32 = 1;
-//pub const GPIO_LINE_CHANGED_RELEASED: u32 = 2;
-//pub const GPIO_LINE_CHANGED_CONFIG: u32 = 3;
+pub const GPIO_LINE_CHANGED_REQUESTED: u32 = 1;
+pub const GPIO_LINE_CHANGED_RELEASED: u32 = 2;
+pub const GPIO_LINE_CHANGED_CONFIG: u32 = 3;
 
 // Line event edge
 pub const GPIO_LINE_EVENT_RISING_EDGE: u32 = 1;
@@ -120,6 +120,12 @@ pub struct GpioLineInfoChanged {
     padding: [u32; 5],
 }
 
+impl AsMut<[u8; core::mem::size_of::<GpioLineInfoChanged>()]> for GpioLineInfoChanged {
+    fn as_mut(&mut self) -> &mut [u8; core::mem::size_of::<GpioLineInfoChanged>()] {
+        unsafe { core::mem::transmute(self) }
+    }
+}
+
 #[derive(Clone, Copy, Default)]
 #[repr(C)]
 pub struct GpioLineEvent {
@@ -1,4 +1,4 @@
-use crate::{utils::*, Error, Result, Time, MAX_BITS};
+use crate::{utils::*, Bits, Error, Result, Time, MAX_BITS};
 use std::{fmt, str};
 
 /// Line offset
@@ -38,6 +38,14 @@ impl LineMap {
         }
         Err(invalid_data("Unknown line offset"))
     }
+
+    /// Get bit position of line offset given by user
+    ///
+    /// Same as [LineMap::get] but fails with [Error::LineNotFound] when line is not requested.
+    pub fn find(&self, line: LineId) -> Result<BitId> {
+        self.get(line)
+            .map_err(|_| Error::LineNotFound(line.to_string()))
+    }
 }
 
 /// The information of a specific GPIO line
@@ -64,6 +72,16 @@ pub struct LineInfo {
     /// GPIO line output drive mode
     pub drive: Drive,
 
+    /// GPIO line input debounce period
+    ///
+    /// Available only with ABI v2.
+    pub debounce: Option<Time>,
+
+    /// GPIO line attributes
+    ///
+    /// Raw attributes reported by kernel. Available only with ABI v2.
+    pub attributes: Vec<LineAttribute>,
+
     /// GPIO line name
     pub name: String,
 
@@ -94,6 +112,9 @@ impl fmt::Display for LineInfo {
         if !matches!(self.drive, Drive::PushPull) {
             write!(f, "\t {}", self.drive)?;
         }
+        if let Some(debounce) = self.debounce {
+            write!(f, "\t debounce {:?}", debounce)?;
+        }
         if self.used {
             write!(f, "\t [used]")?;
         }
@@ -101,8 +122,232 @@ impl fmt::Display for LineInfo {
     }
 }
 
-/// Direction of a GPIO line
+/// Kind of GPIO line info change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+#[repr(u8)]
+pub enum LineChangeKind {
+    /// Line has been requested
+    Requested,
+    /// Line has been released
+    Released,
+    /// Line has been reconfigured
+    Reconfigured,
+}
+
+impl AsRef<str> for LineChangeKind {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Requested => "requested",
+            Self::Released => "released",
+            Self::Reconfigured => "reconfigured",
+        }
+    }
+}
+
+impl fmt::Display for LineChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_ref().fmt(f)
+    }
+}
+
+/// GPIO line info change event
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LineChange {
+    /// GPIO line offset
+    pub line: LineId,
+    /// Kind of change
+    pub kind: LineChangeKind,
+    /// Time when change happened
+    pub time: Time,
+    /// Updated line info
+    pub info: LineInfo,
+}
+
+impl fmt::Display for LineChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        '#'.fmt(f)?;
+        self.line.fmt(f)?;
+        ' '.fmt(f)?;
+        self.kind.fmt(f)?;
+        ' '.fmt(f)?;
+        self.time.as_nanos().fmt(f)
+    }
+}
+
+/// Attribute of a GPIO line
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum LineAttribute {
+    /// Line flags
+    Flags(u64),
+    /// Output values bits
+    OutputValues(Bits),
+    /// Input debounce period
+    Debounce(Time),
+    /// Attribute which is not known yet
+    Unknown {
+        /// Attribute identifier
+        id: u32,
+        /// Raw attribute value
+        value: u64,
+    },
+}
+
+/// Order of bits when shifting out bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+#[repr(u8)]
+pub enum BitOrder {
+    /// Most significant bit first (default)
+    #[default]
+    MsbFirst,
+    /// Least significant bit first
+    LsbFirst,
+}
+
+impl BitOrder {
+    /// Get bits of byte in order
+    pub fn bits(self, byte: u8) -> [bool; 8] {
+        let mut bits = [false; 8];
+        for (i, bit) in bits.iter_mut().enumerate() {
+            let shift = match self {
+                Self::MsbFirst => 7 - i,
+                Self::LsbFirst => i,
+            };
+            *bit = byte & (1 << shift) != 0;
+        }
+        bits
+    }
+}
+
+/// GPIO character device ABI version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+#[repr(u8)]
+pub enum AbiVersion {
+    /// ABI v1 (linux >= 4.0)
+    V1,
+    /// ABI v2 (linux >= 5.10)
+    V2,
+}
+
+impl AsRef<str> for AbiVersion {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::V1 => "v1",
+            Self::V2 => "v2",
+        }
+    }
+}
+
+impl fmt::Display for AbiVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_ref().fmt(f)
+    }
+}
+
+/// Line name matching mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+#[repr(u8)]
+pub enum NameMatch {
+    /// Name is exactly same as pattern (default)
+    #[default]
+    Exact,
+    /// Name is same as pattern ignoring ASCII case
+    IgnoreCase,
+    /// Name starts with pattern
+    Prefix,
+    /// Name matches glob pattern where `*` matches any sequence and `?` matches single char
+    Glob,
+}
+
+impl NameMatch {
+    /// Check that name matches pattern
+    pub fn matches(self, pattern: &str, name: &str) -> bool {
+        match self {
+            Self::Exact => name == pattern,
+            Self::IgnoreCase => name.eq_ignore_ascii_case(pattern),
+            Self::Prefix => name.starts_with(pattern),
+            Self::Glob => glob_match(pattern, name),
+        }
+    }
+}
+
+impl AsRef<str> for NameMatch {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Exact => "exact",
+            Self::IgnoreCase => "ignore-case",
+            Self::Prefix => "prefix",
+            Self::Glob => "glob",
+        }
+    }
+}
+
+impl fmt::Display for NameMatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_ref().fmt(f)
+    }
+}
+
+impl str::FromStr for NameMatch {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "exact" => Self::Exact,
+            "ignore-case" => Self::IgnoreCase,
+            "prefix" => Self::Prefix,
+            "glob" => Self::Glob,
+            _ => return Err(invalid_input("Not recognized name match")),
+        })
+    }
+}
+
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let name = name.chars().collect::<Vec<_>>();
+
+    let (mut p, mut n) = (0, 0);
+    // Position of last star in pattern and position in name where it started to match
+    let mut star = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((star_p, star_n)) => {
+                    // Let the star consume one more char
+                    star = Some((star_p, star_n + 1));
+                    p = star_p + 1;
+                    n = star_n + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Direction of a GPIO line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 #[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
@@ -110,18 +355,13 @@ impl fmt::Display for LineInfo {
 pub enum Direction {
     /// Line acts as input (default)
     #[cfg_attr(feature = "clap", clap(aliases = ["i", "in"]))]
+    #[default]
     Input,
     /// Line acts as output
     #[cfg_attr(feature = "clap", clap(aliases = ["o", "out"]))]
     Output,
 }
 
-impl Default for Direction {
-    fn default() -> Self {
-        Self::Input
-    }
-}
-
 impl AsRef<str> for Direction {
     fn as_ref(&self) -> &str {
         match self {
@@ -155,7 +395,7 @@ impl str::FromStr for Direction {
 /// Otherwise if it is **low** then physical level will be inverted from logical.
 ///
 /// Also this may be treated as polarity.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 #[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
@@ -166,15 +406,10 @@ pub enum Active {
     Low,
     /// Active level is high (default)
     #[cfg_attr(feature = "clap", clap(aliases = ["h", "hi"]))]
+    #[default]
     High,
 }
 
-impl Default for Active {
-    fn default() -> Self {
-        Self::High
-    }
-}
-
 impl AsRef<str> for Active {
     fn as_ref(&self) -> &str {
         match self {
@@ -184,6 +419,15 @@ impl AsRef<str> for Active {
     }
 }
 
+impl Active {
+    /// Convert logical level to physical one
+    ///
+    /// The conversion is symmetric, so it also converts physical level to logical.
+    pub fn physical(self, logical: bool) -> bool {
+        logical != (self == Self::Low)
+    }
+}
+
 impl fmt::Display for Active {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.as_ref().fmt(f)
@@ -244,16 +488,124 @@ impl str::FromStr for Edge {
     }
 }
 
+/// Features supported by GPIO line
+///
+/// Each feature is `Some(true)` when supported, `Some(false)` when rejected by driver
+/// and `None` when unknown because probing would disturb the state of line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LineCapabilities {
+    /// Edge detection
+    pub edge_detection: Option<bool>,
+    /// Pull-up bias
+    pub pull_up: Option<bool>,
+    /// Pull-down bias
+    pub pull_down: Option<bool>,
+    /// Disabling bias
+    pub bias_disable: Option<bool>,
+    /// Open-drain drive
+    ///
+    /// Never probed, so it is known only when line is configured so.
+    pub open_drain: Option<bool>,
+    /// Open-source drive
+    ///
+    /// Never probed, so it is known only when line is configured so.
+    pub open_source: Option<bool>,
+}
+
+impl LineCapabilities {
+    pub(crate) fn bias_mut(&mut self, bias: Bias) -> &mut Option<bool> {
+        match bias {
+            Bias::PullUp => &mut self.pull_up,
+            Bias::PullDown => &mut self.pull_down,
+            Bias::Disable => &mut self.bias_disable,
+        }
+    }
+}
+
 /// Signal edge detection event
 #[derive(Debug, Clone, Copy)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Event {
     /// GPIO line where edge detected
     pub line: BitId,
+    /// GPIO line offset where edge detected
+    pub offset: LineId,
     /// Detected edge or level transition
     pub edge: Edge,
     /// Time when edge actually detected
+    ///
+    /// The meaning depends on [Event::clock]: with [EventClock::Monotonic] it is time since
+    /// system boot (not including suspend), so it cannot be compared with wall-clock time,
+    /// while with [EventClock::Realtime] it is time since UNIX epoch (see [Event::system_time]).
     pub time: Time,
+    /// Clock which was used to timestamp event
+    pub clock: EventClock,
+    /// Sequence number of event in request
+    ///
+    /// Available only with ABI v2.
+    pub seqno: Option<u32>,
+    /// Sequence number of event on line
+    ///
+    /// Available only with ABI v2.
+    pub line_seqno: Option<u32>,
+}
+
+impl Event {
+    /// Get number of events dropped between previous event of the same request and this one
+    ///
+    /// Events are dropped by kernel when buffer overflows, so gaps in sequence numbers
+    /// are counted. Always returns zero when sequence numbers are not available.
+    pub fn dropped_since(&self, previous: &Event) -> u32 {
+        match (self.seqno, previous.seqno) {
+            (Some(seqno), Some(previous)) => seqno.wrapping_sub(previous).saturating_sub(1),
+            _ => 0,
+        }
+    }
+
+    /// Get wall-clock time when edge detected
+    ///
+    /// Available only for events timestamped using [EventClock::Realtime].
+    pub fn system_time(&self) -> Option<std::time::SystemTime> {
+        match self.clock {
+            EventClock::Realtime => Some(std::time::UNIX_EPOCH + self.time),
+            EventClock::Monotonic => None,
+        }
+    }
+
+    /// Format event time in human readable form
+    ///
+    /// Events timestamped using [EventClock::Realtime] are formatted as RFC 3339 UTC date
+    /// and time, while others as seconds with nanoseconds fraction.
+    pub fn format_time(&self) -> String {
+        self.format_time_as(match self.clock {
+            EventClock::Realtime => TimeFormat::Iso,
+            EventClock::Monotonic => TimeFormat::Seconds,
+        })
+    }
+
+    /// Format event time using given format
+    ///
+    /// The [TimeFormat::Iso] is only meaningful for [EventClock::Realtime], so time of other
+    /// events is formatted as seconds instead.
+    pub fn format_time_as(&self, format: TimeFormat) -> String {
+        match (format, self.clock) {
+            (TimeFormat::Nanos, _) => self.time.as_nanos().to_string(),
+            (TimeFormat::Iso, EventClock::Realtime) => format_rfc3339(self.time),
+            _ => format!("{}.{:09}", self.time.as_secs(), self.time.subsec_nanos()),
+        }
+    }
+
+    /// Split event into line offset, edge and time
+    pub fn into_parts(self) -> (LineId, Edge, Time) {
+        (self.offset, self.edge, self.time)
+    }
+}
+
+impl From<Event> for (LineId, Edge, Time) {
+    fn from(event: Event) -> Self {
+        event.into_parts()
+    }
 }
 
 impl fmt::Display for Event {
@@ -263,12 +615,160 @@ impl fmt::Display for Event {
         ' '.fmt(f)?;
         self.edge.fmt(f)?;
         ' '.fmt(f)?;
-        self.time.as_nanos().fmt(f)
+        self.time.as_nanos().fmt(f)?;
+        if let Some(seqno) = self.seqno {
+            " seq ".fmt(f)?;
+            seqno.fmt(f)?;
+        }
+        if let Some(line_seqno) = self.line_seqno {
+            '/'.fmt(f)?;
+            line_seqno.fmt(f)?;
+        }
+        Ok(())
     }
 }
 
-/// Edge detection setting for GPIO line
+/// Signal edge detection event record
+///
+/// Unlike [Event] it contains plain values only, so it can be stored
+/// (with `serde` feature) to replay events later.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EventRecord {
+    /// GPIO line offset where edge detected
+    pub line_offset: LineId,
+    /// GPIO line bit position in request
+    pub line: BitId,
+    /// Detected edge or level transition
+    pub edge: Edge,
+    /// Time in nanoseconds when edge actually detected
+    pub time_ns: u64,
+    /// Clock which was used to timestamp event
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub clock: EventClock,
+    /// Event sequence number when available
+    pub seqno: Option<u32>,
+    /// Line event sequence number when available
+    pub line_seqno: Option<u32>,
+}
+
+impl From<Event> for EventRecord {
+    fn from(event: Event) -> Self {
+        Self {
+            line_offset: event.offset,
+            line: event.line,
+            edge: event.edge,
+            time_ns: event.time.as_nanos() as _,
+            clock: event.clock,
+            seqno: event.seqno,
+            line_seqno: event.line_seqno,
+        }
+    }
+}
+
+impl From<EventRecord> for Event {
+    fn from(record: EventRecord) -> Self {
+        Self {
+            line: record.line,
+            offset: record.line_offset,
+            edge: record.edge,
+            time: time_from_nanos(record.time_ns),
+            clock: record.clock,
+            seqno: record.seqno,
+            line_seqno: record.line_seqno,
+        }
+    }
+}
+
+/// Per-line statistics of events read from single request
+///
+/// Events of all lines in a request are delivered in a single FIFO, so a noisy line can
+/// delay the events of quiet lines. The statistics helps to detect such line.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventStats {
+    lines: Vec<LineEventStats>,
+    total: u64,
+    last_line: Option<BitId>,
+    run: u64,
+}
+
+/// Statistics of events for single line
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LineEventStats {
+    /// Number of events
+    pub count: u64,
+    /// Time of last event
+    pub last_time: Option<Time>,
+    /// Maximum number of events read in a row without events of other lines
+    pub max_run: u64,
+}
+
+impl EventStats {
+    /// Create statistics for specified number of lines
+    pub fn new(lines: usize) -> Self {
+        Self {
+            lines: vec![LineEventStats::default(); lines],
+            ..Default::default()
+        }
+    }
+
+    /// Account event
+    pub fn record(&mut self, event: &Event) {
+        let id = event.line as usize;
+
+        if id >= self.lines.len() {
+            self.lines.resize(id + 1, LineEventStats::default());
+        }
+
+        if self.last_line == Some(event.line) {
+            self.run += 1;
+        } else {
+            self.last_line = Some(event.line);
+            self.run = 1;
+        }
+
+        let line = &mut self.lines[id];
+
+        line.count += 1;
+        line.last_time = Some(event.time);
+        line.max_run = line.max_run.max(self.run);
+
+        self.total += 1;
+    }
+
+    /// Get statistics of line by bit position in request
+    pub fn line(&self, line: BitId) -> LineEventStats {
+        self.lines.get(line as usize).copied().unwrap_or_default()
+    }
+
+    /// Get statistics of all lines in order of request
+    pub fn lines(&self) -> &[LineEventStats] {
+        &self.lines
+    }
+
+    /// Get total number of events
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Get line which has most events
+    pub fn busiest(&self) -> Option<BitId> {
+        self.lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| line.count > 0)
+            .max_by_key(|(_, line)| line.count)
+            .map(|(id, _)| id as _)
+    }
+
+    /// Reset statistics
+    pub fn reset(&mut self) {
+        *self = Self::new(self.lines.len());
+    }
+}
+
+/// Edge detection setting for GPIO line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 #[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
@@ -276,6 +776,7 @@ impl fmt::Display for Event {
 pub enum EdgeDetect {
     /// Detection disabled (default)
     #[cfg_attr(feature = "clap", clap(aliases = ["d", "dis"]))]
+    #[default]
     Disable,
     /// Detect rising edge only
     #[cfg_attr(feature = "clap", clap(aliases = ["r", "rise"]))]
@@ -288,12 +789,6 @@ pub enum EdgeDetect {
     Both,
 }
 
-impl Default for EdgeDetect {
-    fn default() -> Self {
-        Self::Disable
-    }
-}
-
 impl AsRef<str> for EdgeDetect {
     fn as_ref(&self) -> &str {
         match self {
@@ -325,18 +820,142 @@ impl str::FromStr for EdgeDetect {
     }
 }
 
+/// Clock source for edge event timestamps
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+#[repr(u8)]
+pub enum EventClock {
+    /// Monotonic clock, time since system boot (default)
+    #[cfg_attr(feature = "clap", clap(aliases = ["m", "mono"]))]
+    #[default]
+    Monotonic,
+    /// Realtime clock, time since UNIX epoch
+    #[cfg_attr(feature = "clap", clap(aliases = ["r", "real"]))]
+    Realtime,
+}
+
+impl AsRef<str> for EventClock {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Monotonic => "monotonic",
+            Self::Realtime => "realtime",
+        }
+    }
+}
+
+impl fmt::Display for EventClock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_ref().fmt(f)
+    }
+}
+
+impl str::FromStr for EventClock {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "m" | "mono" | "monotonic" => Self::Monotonic,
+            "r" | "real" | "realtime" => Self::Realtime,
+            _ => return Err(invalid_input("Not recognized event clock")),
+        })
+    }
+}
+
+/// Format of edge event timestamps
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+#[repr(u8)]
+pub enum TimeFormat {
+    /// Nanoseconds (default)
+    #[cfg_attr(feature = "clap", clap(aliases = ["n", "ns"]))]
+    #[default]
+    Nanos,
+    /// Seconds with nanoseconds fraction
+    #[cfg_attr(feature = "clap", clap(aliases = ["s", "sec"]))]
+    Seconds,
+    /// RFC 3339 UTC date and time (only for realtime clock)
+    #[cfg_attr(feature = "clap", clap(aliases = ["i", "rfc3339"]))]
+    Iso,
+}
+
+impl AsRef<str> for TimeFormat {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Nanos => "nanos",
+            Self::Seconds => "seconds",
+            Self::Iso => "iso",
+        }
+    }
+}
+
+impl fmt::Display for TimeFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_ref().fmt(f)
+    }
+}
+
+impl str::FromStr for TimeFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "n" | "ns" | "nanos" => Self::Nanos,
+            "s" | "sec" | "seconds" => Self::Seconds,
+            "i" | "rfc3339" | "iso" => Self::Iso,
+            _ => return Err(invalid_input("Not recognized time format")),
+        })
+    }
+}
+
+/// Format time since UNIX epoch as RFC 3339 UTC date and time with nanoseconds
+fn format_rfc3339(time: Time) -> String {
+    let secs = time.as_secs();
+    let days = (secs / 86400) as i64;
+    let rem = secs % 86400;
+
+    // civil from days, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:09}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem / 60 % 60,
+        rem % 60,
+        time.subsec_nanos()
+    )
+}
+
 /// Input bias of a GPIO line
 ///
 /// Sometimes GPIO lines shall be pulled to up (power rail) or down (ground)
 /// through resistor to avoid floating level on it.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 #[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 #[repr(u8)]
 pub enum Bias {
     /// Disabled bias (default)
+    ///
+    /// Requesting it explicitly disables bias unlike leaving bias unconfigured
+    /// (see [Options::bias](crate::Options::bias)).
     #[cfg_attr(feature = "clap", clap(aliases = ["d", "dis"]))]
+    #[default]
     Disable,
     /// Pull line up
     #[cfg_attr(feature = "clap", clap(aliases = ["pu"]))]
@@ -346,12 +965,6 @@ pub enum Bias {
     PullDown,
 }
 
-impl Default for Bias {
-    fn default() -> Self {
-        Self::Disable
-    }
-}
-
 impl AsRef<str> for Bias {
     fn as_ref(&self) -> &str {
         match self {
@@ -375,7 +988,7 @@ impl str::FromStr for Bias {
         Ok(match s {
             "d" | "dis" | "disable" => Self::Disable,
             "pu" | "pull-up" => Self::PullUp,
-            "pd" | "pull-down" => Self::PullUp,
+            "pd" | "pull-down" => Self::PullDown,
             _ => return Err(invalid_input("Not recognized input bias")),
         })
     }
@@ -384,7 +997,7 @@ impl str::FromStr for Bias {
 /// Output drive mode of a GPIO line
 ///
 /// Usually GPIO lines configured as push-pull but sometimes it required to drive via open drain or source.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 #[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
@@ -392,6 +1005,7 @@ impl str::FromStr for Bias {
 pub enum Drive {
     /// Drive push-pull (default)
     #[cfg_attr(feature = "clap", clap(aliases = ["pp"]))]
+    #[default]
     PushPull,
     /// Drive with open-drain
     #[cfg_attr(feature = "clap", clap(aliases = ["od"]))]
@@ -401,12 +1015,6 @@ pub enum Drive {
     OpenSource,
 }
 
-impl Default for Drive {
-    fn default() -> Self {
-        Self::PushPull
-    }
-}
-
 impl AsRef<str> for Drive {
     fn as_ref(&self) -> &str {
         match self {
@@ -435,3 +1043,297 @@ impl str::FromStr for Drive {
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn event_format_time() {
+        let mut event = Event {
+            line: 0,
+            offset: 3,
+            edge: Edge::Rising,
+            time: Time::new(1_700_000_000, 123_456_789),
+            clock: EventClock::Realtime,
+            seqno: None,
+            line_seqno: None,
+        };
+
+        assert_eq!(
+            event.format_time_as(TimeFormat::Nanos),
+            "1700000000123456789"
+        );
+        assert_eq!(
+            event.format_time_as(TimeFormat::Seconds),
+            "1700000000.123456789"
+        );
+        assert_eq!(
+            event.format_time_as(TimeFormat::Iso),
+            "2023-11-14T22:13:20.123456789Z"
+        );
+        assert_eq!(event.format_time(), "2023-11-14T22:13:20.123456789Z");
+
+        event.time = Time::new(951_782_400, 5);
+        assert_eq!(event.format_time(), "2000-02-29T00:00:00.000000005Z");
+
+        event.time = Time::new(408914, 219966626);
+        event.clock = EventClock::Monotonic;
+
+        assert_eq!(event.format_time(), "408914.219966626");
+        assert_eq!(event.format_time_as(TimeFormat::Iso), "408914.219966626");
+    }
+
+    #[test]
+    fn event_record() {
+        let event = Event {
+            line: 1,
+            offset: 17,
+            edge: Edge::Falling,
+            time: Time::from_nanos(408914219966626),
+            clock: EventClock::Realtime,
+            seqno: Some(5),
+            line_seqno: Some(2),
+        };
+
+        let record = EventRecord::from(event);
+
+        assert_eq!(record.line_offset, 17);
+        assert_eq!(record.time_ns, 408914219966626);
+
+        let event = Event::from(record);
+
+        assert_eq!(event.line, 1);
+        assert_eq!(event.offset, 17);
+        assert_eq!(event.edge, Edge::Falling);
+        assert_eq!(event.time, Time::from_nanos(408914219966626));
+        assert_eq!(event.clock, EventClock::Realtime);
+        assert_eq!(event.seqno, Some(5));
+        assert_eq!(event.line_seqno, Some(2));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn event_record_serde() {
+        let record = EventRecord {
+            line_offset: 3,
+            line: 0,
+            edge: Edge::Rising,
+            time_ns: 1000,
+            clock: EventClock::Realtime,
+            seqno: Some(1),
+            line_seqno: Some(1),
+        };
+
+        let json = serde_json::to_string(&record).unwrap();
+        assert_eq!(
+            json,
+            r#"{"line_offset":3,"line":0,"edge":"rising","time_ns":1000,"clock":"realtime","seqno":1,"line_seqno":1}"#
+        );
+        assert_eq!(serde_json::from_str::<EventRecord>(&json).unwrap(), record);
+
+        // records stored without clock and line sequence number
+        let record = serde_json::from_str::<EventRecord>(
+            r#"{"line_offset":3,"line":0,"edge":"rising","time_ns":1000,"seqno":null}"#,
+        )
+        .unwrap();
+        assert_eq!(record.clock, EventClock::Monotonic);
+        assert_eq!(record.line_seqno, None);
+    }
+
+    #[test]
+    fn active_physical() {
+        assert!(Active::High.physical(true));
+        assert!(!Active::High.physical(false));
+        assert!(!Active::Low.physical(true));
+        assert!(Active::Low.physical(false));
+        assert!(Active::Low.physical(Active::Low.physical(true)));
+    }
+
+    #[test]
+    fn event_stats() {
+        let event = |line, time| Event {
+            line,
+            offset: line as LineId + 10,
+            edge: Edge::Rising,
+            time: Time::from_millis(time),
+            clock: EventClock::Monotonic,
+            seqno: None,
+            line_seqno: None,
+        };
+
+        let mut stats = EventStats::new(3);
+
+        assert_eq!(stats.busiest(), None);
+
+        for (line, time) in [(0, 1), (0, 2), (0, 3), (2, 4), (0, 5)] {
+            stats.record(&event(line, time));
+        }
+
+        assert_eq!(stats.total(), 5);
+        assert_eq!(stats.busiest(), Some(0));
+        assert_eq!(
+            stats.line(0),
+            LineEventStats {
+                count: 4,
+                last_time: Some(Time::from_millis(5)),
+                max_run: 3,
+            }
+        );
+        assert_eq!(stats.line(1), LineEventStats::default());
+        assert_eq!(stats.line(2).count, 1);
+
+        stats.reset();
+
+        assert_eq!(stats, EventStats::new(3));
+    }
+
+    #[test]
+    fn bit_order() {
+        assert_eq!(
+            BitOrder::MsbFirst.bits(0b1000_0011),
+            [true, false, false, false, false, false, true, true]
+        );
+        assert_eq!(
+            BitOrder::LsbFirst.bits(0b1000_0011),
+            [true, true, false, false, false, false, false, true]
+        );
+    }
+
+    #[test]
+    fn event_parts() {
+        let event = Event {
+            line: 1,
+            offset: 17,
+            edge: Edge::Falling,
+            time: Time::from_nanos(42),
+            clock: EventClock::Monotonic,
+            seqno: None,
+            line_seqno: None,
+        };
+
+        let (line, edge, time) = event.into();
+
+        assert_eq!(line, 17);
+        assert_eq!(edge, Edge::Falling);
+        assert_eq!(time, Time::from_nanos(42));
+    }
+
+    #[test]
+    fn event_dropped() {
+        let event = |seqno| Event {
+            line: 0,
+            offset: 3,
+            edge: Edge::Rising,
+            time: Time::from_nanos(0),
+            clock: EventClock::Monotonic,
+            seqno,
+            line_seqno: seqno,
+        };
+
+        assert_eq!(event(Some(2)).dropped_since(&event(Some(1))), 0);
+        assert_eq!(event(Some(5)).dropped_since(&event(Some(1))), 3);
+        assert_eq!(event(Some(0)).dropped_since(&event(Some(u32::MAX))), 0);
+        assert_eq!(event(Some(5)).dropped_since(&event(None)), 0);
+        assert_eq!(event(None).dropped_since(&event(None)), 0);
+    }
+
+    #[test]
+    fn name_match() {
+        assert!(NameMatch::Exact.matches("GPIO23", "GPIO23"));
+        assert!(!NameMatch::Exact.matches("GPIO23", "gpio23"));
+        assert!(NameMatch::IgnoreCase.matches("GPIO23", "gpio23"));
+        assert!(NameMatch::Prefix.matches("led", "led_status"));
+        assert!(!NameMatch::Prefix.matches("led", "status_led"));
+
+        assert!(NameMatch::Glob.matches("led*", "led_status"));
+        assert!(NameMatch::Glob.matches("*led", "status_led"));
+        assert!(NameMatch::Glob.matches("*", ""));
+        assert!(NameMatch::Glob.matches("gpio?23", "gpio-23"));
+        assert!(NameMatch::Glob.matches("g*o*3", "gpio-23"));
+        assert!(!NameMatch::Glob.matches("gpio?23", "gpio23"));
+        assert!(!NameMatch::Glob.matches("led*", "status_led"));
+        assert!(!NameMatch::Glob.matches("", "led"));
+    }
+
+    #[test]
+    fn bias_parse() {
+        assert_eq!("pd".parse::<Bias>().unwrap(), Bias::PullDown);
+        assert_eq!("pull-down".parse::<Bias>().unwrap(), Bias::PullDown);
+        assert_eq!("pu".parse::<Bias>().unwrap(), Bias::PullUp);
+        assert_eq!("d".parse::<Bias>().unwrap(), Bias::Disable);
+        assert!("pull".parse::<Bias>().is_err());
+
+        for bias in [Bias::Disable, Bias::PullUp, Bias::PullDown] {
+            assert_eq!(bias.to_string().parse::<Bias>().unwrap(), bias);
+        }
+    }
+
+    #[test]
+    fn event_display() {
+        let mut event = Event {
+            line: 1,
+            offset: 17,
+            edge: Edge::Falling,
+            time: Time::from_nanos(42),
+            clock: EventClock::Monotonic,
+            seqno: None,
+            line_seqno: None,
+        };
+
+        assert_eq!(event.to_string(), "#1 falling 42");
+
+        event.seqno = Some(5);
+        event.line_seqno = Some(2);
+
+        assert_eq!(event.to_string(), "#1 falling 42 seq 5/2");
+    }
+
+    #[test]
+    fn event_system_time() {
+        let mut event = Event {
+            line: 0,
+            offset: 3,
+            edge: Edge::Rising,
+            time: Time::from_secs(1_700_000_000),
+            clock: EventClock::Monotonic,
+            seqno: None,
+            line_seqno: None,
+        };
+
+        assert_eq!(event.system_time(), None);
+
+        event.clock = EventClock::Realtime;
+
+        assert_eq!(
+            event.system_time(),
+            Some(std::time::UNIX_EPOCH + Time::from_secs(1_700_000_000))
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_enums() {
+        fn round_trip<T>(value: T, json: &str)
+        where
+            T: serde::Serialize + serde::de::DeserializeOwned + PartialEq + fmt::Debug,
+        {
+            assert_eq!(serde_json::to_string(&value).unwrap(), json);
+            assert_eq!(serde_json::from_str::<T>(json).unwrap(), value);
+        }
+
+        round_trip(Direction::Input, r#""input""#);
+        round_trip(Direction::Output, r#""output""#);
+        round_trip(Active::Low, r#""low""#);
+        round_trip(Active::High, r#""high""#);
+        round_trip(Bias::PullUp, r#""pull-up""#);
+        round_trip(Bias::PullDown, r#""pull-down""#);
+        round_trip(Bias::Disable, r#""disable""#);
+        round_trip(Drive::PushPull, r#""push-pull""#);
+        round_trip(Drive::OpenDrain, r#""open-drain""#);
+        round_trip(Drive::OpenSource, r#""open-source""#);
+        round_trip(EdgeDetect::Rising, r#""rising""#);
+        round_trip(EdgeDetect::Both, r#""both""#);
+        round_trip(EventClock::Realtime, r#""realtime""#);
+    }
+}
@@ -16,12 +16,12 @@ where
 
 #[inline(always)]
 pub fn invalid_input(msg: &'static str) -> Error {
-    Error::new(io::ErrorKind::InvalidInput, msg)
+    Error::Io(io::Error::new(io::ErrorKind::InvalidInput, msg))
 }
 
 #[inline(always)]
 pub fn invalid_data(msg: &'static str) -> Error {
-    Error::new(io::ErrorKind::InvalidData, msg)
+    Error::Io(io::Error::new(io::ErrorKind::InvalidData, msg))
 }
 
 #[inline(always)]
@@ -38,13 +38,13 @@ pub fn check_len<V, T: ?Sized>(slice: &[V], val: &T) -> Result<()> {
     if slice.len() <= size_of_val(val) {
         Ok(())
     } else {
-        Err(invalid_input("Too many values"))
+        Err(Error::TooManyLines)
     }
 }
 
 #[inline(always)]
 pub fn check_len_str<T: ?Sized>(slice: &str, val: &T) -> Result<()> {
-    if slice.as_bytes().len() /* \0 */ < size_of_val(val) {
+    if slice.len() /* \0 */ < size_of_val(val) {
         Ok(())
     } else {
         Err(invalid_input("String too long"))
@@ -69,6 +69,16 @@ pub fn safe_get_str(src: &[u8]) -> Result<&str> {
         .trim_end_matches('\0'))
 }
 
+/// Repeat operation while it is interrupted by signal
+pub fn retry_interrupted<T>(mut op: impl FnMut() -> Result<T>) -> Result<T> {
+    loop {
+        match op() {
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+            result => return result,
+        }
+    }
+}
+
 /// This definition from libc
 #[inline(always)]
 pub fn major(dev: u64) -> u64 {
@@ -89,13 +99,154 @@ pub fn minor(dev: u64) -> u64 {
 
 /// Set non-block flag to fd
 pub fn set_nonblock(fd: std::os::unix::io::RawFd) -> Result<()> {
+    set_nonblocking(fd, true)
+}
+
+/// Set or clear non-block flag of fd
+pub fn set_nonblocking(fd: std::os::unix::io::RawFd, nonblocking: bool) -> Result<()> {
     use nix::fcntl::{fcntl, FcntlArg, OFlag};
 
     let mut flags = OFlag::from_bits_truncate(fcntl(fd, FcntlArg::F_GETFL)?);
 
-    flags.insert(OFlag::O_NONBLOCK);
+    flags.set(OFlag::O_NONBLOCK, nonblocking);
 
     fcntl(fd, FcntlArg::F_SETFL(flags))?;
 
     Ok(())
 }
+
+/// Close fd reporting failure
+pub fn close_fd(fd: std::os::unix::io::RawFd) -> Result<()> {
+    nix::unistd::close(fd)?;
+    Ok(())
+}
+
+/// Check non-block flag of fd
+pub fn is_nonblocking(fd: std::os::unix::io::RawFd) -> Result<bool> {
+    use nix::fcntl::{fcntl, FcntlArg, OFlag};
+
+    let flags = OFlag::from_bits_truncate(fcntl(fd, FcntlArg::F_GETFL)?);
+
+    Ok(flags.contains(OFlag::O_NONBLOCK))
+}
+
+/// Check that fd is opened for writing
+pub fn is_writable(fd: std::os::unix::io::RawFd) -> Result<bool> {
+    use nix::fcntl::{fcntl, FcntlArg, OFlag};
+
+    let flags = OFlag::from_bits_truncate(fcntl(fd, FcntlArg::F_GETFL)?);
+
+    Ok(matches!(
+        flags & OFlag::O_ACCMODE,
+        OFlag::O_WRONLY | OFlag::O_RDWR
+    ))
+}
+
+/// Wait until fd becomes readable
+///
+/// Returns `false` when timeout elapsed before fd became readable.
+/// The `None` timeout means waiting infinitely.
+///
+/// Interrupted waiting is restarted with remaining time, so signals does not extend timeout.
+pub fn poll_readable(fd: std::os::unix::io::RawFd, timeout: Option<Time>) -> Result<bool> {
+    Ok(poll_readable_any(&[fd], timeout)?.is_some())
+}
+
+/// Wait until any of fds becomes readable
+///
+/// Returns index of the first readable fd or `None` when timeout elapsed.
+/// See [poll_readable] for the details.
+pub fn poll_readable_any(
+    fds: &[std::os::unix::io::RawFd],
+    timeout: Option<Time>,
+) -> Result<Option<usize>> {
+    use nix::{
+        errno::Errno,
+        poll::{poll, PollFd, PollFlags},
+    };
+
+    let deadline = timeout.map(|timeout| time::Instant::now() + timeout);
+
+    let mut fds = fds
+        .iter()
+        .map(|fd| PollFd::new(*fd, PollFlags::POLLIN))
+        .collect::<Vec<_>>();
+
+    loop {
+        let timeout = deadline
+            .map(|deadline| {
+                // Round up to not wake before deadline
+                let remaining = deadline.saturating_duration_since(time::Instant::now());
+                remaining.as_micros().div_ceil(1000).min(i32::MAX as _) as _
+            })
+            .unwrap_or(-1);
+
+        match poll(&mut fds, timeout) {
+            Ok(_) => {
+                return Ok(fds.iter().position(|fd| {
+                    fd.revents()
+                        .map(|events| !events.is_empty())
+                        .unwrap_or(false)
+                }))
+            }
+            Err(Errno::EINTR) => continue,
+            Err(error) => return Err(error.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn poll_readable_timeout() {
+        use std::{fs::File, io::Write, os::unix::io::FromRawFd, time::Instant};
+
+        let (reader, writer) = nix::unistd::pipe().unwrap();
+        let _reader = unsafe { File::from_raw_fd(reader) };
+        let mut writer = unsafe { File::from_raw_fd(writer) };
+
+        assert!(!poll_readable(reader, Some(Time::ZERO)).unwrap());
+
+        let start = Instant::now();
+        assert!(!poll_readable(reader, Some(Time::from_millis(20))).unwrap());
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Time::from_millis(20));
+        assert!(elapsed < Time::from_secs(1));
+
+        writer.write_all(&[0]).unwrap();
+        assert!(poll_readable(reader, Some(Time::ZERO)).unwrap());
+        assert!(poll_readable(reader, Some(Time::from_millis(20))).unwrap());
+        assert!(poll_readable(reader, None).unwrap());
+    }
+
+    #[test]
+    fn poll_readable_any_index() {
+        use std::{fs::File, io::Write, os::unix::io::FromRawFd};
+
+        let (reader1, writer1) = nix::unistd::pipe().unwrap();
+        let (reader2, writer2) = nix::unistd::pipe().unwrap();
+        let _reader1 = unsafe { File::from_raw_fd(reader1) };
+        let _reader2 = unsafe { File::from_raw_fd(reader2) };
+        let _writer1 = unsafe { File::from_raw_fd(writer1) };
+        let mut writer2 = unsafe { File::from_raw_fd(writer2) };
+
+        let fds = [reader1, reader2];
+
+        assert_eq!(poll_readable_any(&fds, Some(Time::ZERO)).unwrap(), None);
+
+        writer2.write_all(&[0]).unwrap();
+        assert_eq!(poll_readable_any(&fds, Some(Time::ZERO)).unwrap(), Some(1));
+        assert_eq!(poll_readable_any(&fds, None).unwrap(), Some(1));
+    }
+
+    #[test]
+    fn close_fd_errors() {
+        let (reader, writer) = nix::unistd::pipe().unwrap();
+
+        assert!(close_fd(reader).is_ok());
+        assert!(close_fd(writer).is_ok());
+        assert!(close_fd(-1).is_err());
+    }
+}
@@ -1,5 +1,10 @@
-use crate::{invalid_input, BitId, Error, Result};
-use std::{fmt, str};
+use crate::{invalid_input, BitId, Error, LineId, Result};
+use std::{
+    collections::{BTreeSet, HashSet},
+    fmt,
+    hash::BuildHasher,
+    ops, str,
+};
 
 /// Value bits and mask
 pub type Bits = u64;
@@ -36,6 +41,32 @@ pub trait AsValues {
         self.copy_into(&mut other);
         other
     }
+
+    /// Number of values which is set to `true`
+    fn count_high(&self) -> u32 {
+        (0..self.bits())
+            .filter(|id| self.get(*id) == Some(true))
+            .count() as _
+    }
+
+    /// Number of values which is set (masked)
+    fn count_masked(&self) -> u32 {
+        (0..self.bits())
+            .filter(|id| self.get(*id).is_some())
+            .count() as _
+    }
+
+    /// Check that any value is set to `true`
+    fn any_high(&self) -> bool {
+        (0..self.bits()).any(|id| self.get(id) == Some(true))
+    }
+
+    /// Check that all set (masked) values is `true`
+    ///
+    /// Returns `true` when no values is set.
+    fn all_high(&self) -> bool {
+        (0..self.bits()).all(|id| self.get(id) != Some(false))
+    }
 }
 
 /// Something that can be used to get and set GPIO line values
@@ -70,6 +101,15 @@ pub trait AsValuesMut: AsValues {
         }
     }
 
+    /// Set values of listed bits
+    ///
+    /// Bits which is not listed are kept as is, bits out of range are ignored.
+    fn set_pairs(&mut self, pairs: &[(BitId, bool)]) {
+        for &(id, val) in pairs {
+            self.set(id, Some(val));
+        }
+    }
+
     /// Truncate mask
     fn truncate(&mut self, len: BitId) {
         for id in len..self.bits() {
@@ -115,6 +155,101 @@ pub struct Masked<Bits> {
     pub mask: Bits,
 }
 
+/// Line values bounded by number of lines
+///
+/// Unlike [Masked] it considers only first `width` lines in [AsValues] implementation,
+/// formatting and comparison. Use [Masked::with_width] to create it.
+#[derive(Debug, Clone, Copy)]
+pub struct WidthValues<Bits> {
+    values: Masked<Bits>,
+    width: BitId,
+}
+
+impl<Bits> WidthValues<Bits> {
+    /// Get number of lines
+    pub fn width(&self) -> BitId {
+        self.width
+    }
+}
+
+impl<Bits> WidthValues<Bits>
+where
+    Masked<Bits>: AsValuesMut + Default,
+{
+    /// Get values truncated to number of lines
+    pub fn values(&self) -> Masked<Bits> {
+        self.convert()
+    }
+}
+
+impl<Bits> AsValues for WidthValues<Bits>
+where
+    Masked<Bits>: AsValues,
+{
+    fn bits(&self) -> BitId {
+        self.width
+    }
+
+    fn get(&self, id: BitId) -> Option<bool> {
+        if id < self.width {
+            self.values.get(id)
+        } else {
+            None
+        }
+    }
+}
+
+impl<Bits> AsValuesMut for WidthValues<Bits>
+where
+    Masked<Bits>: AsValuesMut,
+{
+    fn set(&mut self, id: BitId, val: Option<bool>) {
+        if id < self.width {
+            self.values.set(id, val);
+        }
+    }
+}
+
+impl<Bits> PartialEq for WidthValues<Bits>
+where
+    Masked<Bits>: AsValues,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.width == other.width && (0..self.width).all(|id| self.get(id) == other.get(id))
+    }
+}
+
+impl<Bits> Eq for WidthValues<Bits> where Masked<Bits>: AsValues {}
+
+impl<Bits> fmt::Binary for WidthValues<Bits>
+where
+    Masked<Bits>: AsValues,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut s = String::with_capacity(self.width as usize + 2);
+        if f.alternate() {
+            s.push_str("0b");
+        }
+        for id in (0..self.width).rev() {
+            s.push(match self.get(id) {
+                Some(true) => '1',
+                Some(false) => '0',
+                None => 'x',
+            });
+        }
+        f.pad(&s)
+    }
+}
+
+impl<Bits> fmt::Display for WidthValues<Bits>
+where
+    Masked<Bits>: AsValues,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Binary::fmt(self, f)
+    }
+}
+
 macro_rules! as_values {
     ($($type:ty,)*) => {
         $(
@@ -208,6 +343,30 @@ macro_rules! as_values {
                 }
             }
 
+            impl From<Vec<bool>> for Masked<$type> {
+                fn from(values: Vec<bool>) -> Self {
+                    let mut masked = Self::default();
+                    masked.copy_from(&values);
+                    masked
+                }
+            }
+
+            impl From<Masked<$type>> for Vec<bool> {
+                fn from(values: Masked<$type>) -> Self {
+                    let len = (<$type>::BITS - values.mask.leading_zeros()) as BitId;
+
+                    (0..len).map(|id| values.get(id).unwrap_or(false)).collect()
+                }
+            }
+
+            impl From<Masked<$type>> for Vec<Option<bool>> {
+                fn from(values: Masked<$type>) -> Self {
+                    let len = (<$type>::BITS - values.mask.leading_zeros()) as BitId;
+
+                    (0..len).map(|id| values.get(id)).collect()
+                }
+            }
+
             impl fmt::Binary for Masked<$type> {
                 fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
                     use fmt::Write;
@@ -216,27 +375,30 @@ macro_rules! as_values {
                     let len = (max - (self.mask & self.bits).leading_zeros() as BitId).max(1);
                     let fill = f.width().map(|width| {
                         let width = if f.alternate() {
-                            width - 2
+                            width.saturating_sub(2)
                         } else {
                             width
                         };
-                        if width > len as _ {
-                            width - len as usize
-                        } else {
-                            0
-                        }
+                        width.saturating_sub(len as _)
                     }).unwrap_or(0);
+                    let zero_pad = f.sign_aware_zero_pad();
                     let (fill_before, fill_after) = match f.align() {
+                        _ if zero_pad => (0, 0),
                         Some(fmt::Alignment::Left) => (0, fill),
                         Some(fmt::Alignment::Right) | None => (fill, 0),
                         Some(fmt::Alignment::Center) => (fill - fill / 2, fill / 2),
                     };
                     let fill_char = f.fill();
+                    for _ in 0..fill_before {
+                        f.write_char(fill_char)?;
+                    }
                     if f.alternate() {
                         f.write_str("0b")?;
                     }
-                    for _ in 0..fill_before {
-                        f.write_char(fill_char)?;
+                    if zero_pad {
+                        for _ in 0..fill {
+                            f.write_char('0')?;
+                        }
                     }
                     for i in (0..len).rev() {
                         f.write_char(match self.get(i) {
@@ -258,10 +420,180 @@ macro_rules! as_values {
                 }
             }
 
+            impl From<&[(BitId, bool)]> for Masked<$type> {
+                fn from(pairs: &[(BitId, bool)]) -> Self {
+                    Self::from_pairs(pairs)
+                }
+            }
+
+            impl Masked<$type> {
+                /// Create values from pairs of bit offset and value
+                ///
+                /// Only listed bits are masked, so the rest of values is left unset.
+                /// This is convenient for sparse writes using [AsValuesMut] values.
+                pub fn from_pairs(pairs: &[(BitId, bool)]) -> Self {
+                    let mut r = Self::default();
+                    r.set_pairs(pairs);
+                    r
+                }
+
+                /// Reverse order of values within the low `width` bits
+                ///
+                /// The values above width will be dropped.
+                /// The width will be limited by number of bits.
+                pub fn reverse_bits(&self, width: BitId) -> Self {
+                    let max = (core::mem::size_of::<$type>() * 8) as BitId;
+                    let width = width.min(max);
+                    if width == 0 {
+                        return Self::default();
+                    }
+                    let shift = max - width;
+                    Self {
+                        bits: (self.bits << shift).reverse_bits(),
+                        mask: (self.mask << shift).reverse_bits(),
+                    }
+                }
+
+                /// Merge values overriding masked ones
+                ///
+                /// The values which masked in `other` replaces values of `self`,
+                /// the rest of values are kept from `self`.
+                pub fn merge(self, other: Self) -> Self {
+                    Self {
+                        bits: (self.bits & self.mask & !other.mask) | (other.bits & other.mask),
+                        mask: self.mask | other.mask,
+                    }
+                }
+
+                /// Bound values to specified number of lines
+                ///
+                /// The width will be limited by number of bits.
+                pub fn with_width(self, width: BitId) -> WidthValues<$type> {
+                    WidthValues {
+                        values: self,
+                        width: width.min((core::mem::size_of::<$type>() * 8) as _),
+                    }
+                }
+
+                /// Parse values in order of lines
+                ///
+                /// Unlike [str::FromStr] implementation, which treats string as binary number
+                /// (leftmost char is the highest bit), this treats leftmost char as the first line
+                /// (bit 0), so `"100"` means that only first line is set.
+                /// The `1` and `0` means values and `x` means unset value.
+                pub fn from_line_order(s: &str) -> Result<Self> {
+                    if s.len() > (core::mem::size_of::<$type>() * 8) as _ {
+                        return Err(invalid_input("Too many line values"));
+                    }
+                    let mut r = Self::default();
+                    for (i, c) in s.chars().enumerate() {
+                        match c {
+                            '1' => {
+                                let b = 1 << i;
+                                r.bits |= b;
+                                r.mask |= b;
+                            }
+                            '0' => {
+                                let b = 1 << i;
+                                r.mask |= b;
+                            }
+                            'x' => {}
+                            _ => return Err(invalid_input("Unexpected char in line value")),
+                        }
+                    }
+                    Ok(r)
+                }
+            }
+
+            /// Combine values using logic OR
+            ///
+            /// Resulting mask is union of masks, unset values are treated as low.
+            impl ops::BitOr for Masked<$type> {
+                type Output = Self;
+
+                fn bitor(self, other: Self) -> Self {
+                    Self {
+                        bits: (self.bits & self.mask) | (other.bits & other.mask),
+                        mask: self.mask | other.mask,
+                    }
+                }
+            }
+
+            /// Combine values using logic AND
+            ///
+            /// Resulting mask is intersection of masks.
+            impl ops::BitAnd for Masked<$type> {
+                type Output = Self;
+
+                fn bitand(self, other: Self) -> Self {
+                    let mask = self.mask & other.mask;
+
+                    Self {
+                        bits: self.bits & other.bits & mask,
+                        mask,
+                    }
+                }
+            }
+
+            /// Combine values using logic XOR
+            ///
+            /// Resulting mask is union of masks, unset values are treated as low.
+            impl ops::BitXor for Masked<$type> {
+                type Output = Self;
+
+                fn bitxor(self, other: Self) -> Self {
+                    Self {
+                        bits: (self.bits & self.mask) ^ (other.bits & other.mask),
+                        mask: self.mask | other.mask,
+                    }
+                }
+            }
+
+            /// Invert values
+            ///
+            /// Only set values are inverted, the mask is kept as is.
+            impl ops::Not for Masked<$type> {
+                type Output = Self;
+
+                fn not(self) -> Self {
+                    Self {
+                        bits: !self.bits & self.mask,
+                        mask: self.mask,
+                    }
+                }
+            }
+
+            /// Parse values as binary, hexadecimal or decimal number
+            ///
+            /// Binary is used by default: the leftmost char is the highest bit, optional `0b`
+            /// prefix is allowed and `x` means unset value.
+            /// See [Masked::from_line_order] to parse values in order of lines.
+            ///
+            /// Numbers with `0x` prefix are parsed as hexadecimal and numbers with `0d` prefix
+            /// are parsed as decimal. In both cases all values are set. Unprefixed numbers are
+            /// always binary, so `"12"` is an error rather than decimal twelve.
             impl str::FromStr for Masked<$type> {
                 type Err = Error;
 
                 fn from_str(s: &str) -> Result<Self> {
+                    let number = s
+                        .strip_prefix("0x")
+                        .map(|s| (s, 16))
+                        .or_else(|| s.strip_prefix("0d").map(|s| (s, 10)));
+
+                    if let Some((digits, radix)) = number {
+                        // from_str_radix also accepts sign
+                        if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+                            return Err(invalid_input("Invalid line values number"));
+                        }
+                        let bits = <$type>::from_str_radix(digits, radix)
+                            .map_err(|_| invalid_input("Line values out of range"))?;
+                        return Ok(Self {
+                            bits,
+                            mask: <$type>::MAX,
+                        });
+                    }
+
                     let s = s.strip_prefix("0b").unwrap_or(s);
                     let mut i = s.len() as BitId;
                     if i > (core::mem::size_of::<$type>() * 8) as _ {
@@ -288,10 +620,117 @@ macro_rules! as_values {
                 }
             }
 
+            /// Serialize values as binary number string (ex. `"10xx01"`)
+            ///
+            /// Unlike [fmt::Display] all values up to the highest set one are written,
+            /// so it can be deserialized back without loss.
+            #[cfg(feature = "serde")]
+            impl serde::Serialize for Masked<$type> {
+                fn serialize<S: serde::Serializer>(
+                    &self,
+                    serializer: S,
+                ) -> core::result::Result<S::Ok, S::Error> {
+                    let len = (<$type>::BITS - self.mask.leading_zeros()) as BitId;
+
+                    let mut s = (0..len)
+                        .rev()
+                        .map(|id| match self.get(id) {
+                            Some(true) => '1',
+                            Some(false) => '0',
+                            None => 'x',
+                        })
+                        .collect::<String>();
+
+                    // avoid parsing as hexadecimal
+                    if s.starts_with("0x") {
+                        s.insert_str(0, "0b");
+                    }
+
+                    serializer.serialize_str(&s)
+                }
+            }
+
+            #[cfg(feature = "serde")]
+            impl<'de> serde::Deserialize<'de> for Masked<$type> {
+                fn deserialize<D: serde::Deserializer<'de>>(
+                    deserializer: D,
+                ) -> core::result::Result<Self, D::Error> {
+                    let s = String::deserialize(deserializer)?;
+
+                    s.parse().map_err(serde::de::Error::custom)
+                }
+            }
         )*
     };
 }
 
+/// Iterator over values of [Masked]
+///
+/// Yields values from first line up to the highest set one.
+#[derive(Debug, Clone)]
+pub struct MaskedIter<Bits> {
+    values: Masked<Bits>,
+    id: BitId,
+    len: BitId,
+}
+
+impl<Bits> Iterator for MaskedIter<Bits>
+where
+    Masked<Bits>: AsValues,
+{
+    type Item = Option<bool>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.id < self.len {
+            let value = self.values.get(self.id);
+            self.id += 1;
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.len - self.id) as usize;
+        (len, Some(len))
+    }
+}
+
+impl<Bits> ExactSizeIterator for MaskedIter<Bits> where Masked<Bits>: AsValues {}
+
+impl<Bits> IntoIterator for Masked<Bits>
+where
+    Masked<Bits>: AsValues,
+{
+    type Item = Option<bool>;
+    type IntoIter = MaskedIter<Bits>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let len = (0..self.bits())
+            .rev()
+            .find(|id| self.get(*id).is_some())
+            .map_or(0, |id| id + 1);
+
+        MaskedIter {
+            values: self,
+            id: 0,
+            len,
+        }
+    }
+}
+
+impl<Bits: Copy> IntoIterator for &Masked<Bits>
+where
+    Masked<Bits>: AsValues,
+{
+    type Item = Option<bool>;
+    type IntoIter = MaskedIter<Bits>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        (*self).into_iter()
+    }
+}
+
 as_values! {
     u8,
     u16,
@@ -449,6 +888,110 @@ impl<const LEN: usize> AsValuesMut for [Option<bool>; LEN] {
     }
 }
 
+/// Packed bits in bytes
+///
+/// Bit `id` is stored in bit `id % 8` of byte `id / 8` (little-endian order),
+/// which is convenient for shifting raw register data.
+/// Bits beyond `len() * 8` (or [MAX_BITS]) are treated as `None`.
+impl AsValues for [u8] {
+    fn bits(&self) -> BitId {
+        (self.len() * 8).min(MAX_BITS as _) as _
+    }
+
+    fn get(&self, id: BitId) -> Option<bool> {
+        if id >= self.bits() {
+            return None;
+        }
+
+        Some(self[id as usize / 8] & (1 << (id % 8)) != 0)
+    }
+}
+
+impl AsValuesMut for [u8] {
+    fn set(&mut self, id: BitId, val: Option<bool>) {
+        if id >= self.bits() {
+            return;
+        }
+
+        let mask = 1 << (id % 8);
+
+        match val {
+            Some(true) => self[id as usize / 8] |= mask,
+            Some(false) => self[id as usize / 8] &= !mask,
+            None => {}
+        }
+    }
+}
+
+impl AsValues for Vec<u8> {
+    fn bits(&self) -> BitId {
+        self.as_slice().bits()
+    }
+
+    fn get(&self, id: BitId) -> Option<bool> {
+        AsValues::get(self.as_slice(), id)
+    }
+}
+
+impl AsValuesMut for Vec<u8> {
+    fn set(&mut self, id: BitId, val: Option<bool>) {
+        self.as_mut_slice().set(id, val)
+    }
+}
+
+impl<const LEN: usize> AsValues for [u8; LEN] {
+    fn bits(&self) -> BitId {
+        self.as_slice().bits()
+    }
+
+    fn get(&self, id: BitId) -> Option<bool> {
+        AsValues::get(self.as_slice(), id)
+    }
+}
+
+impl<const LEN: usize> AsValuesMut for [u8; LEN] {
+    fn set(&mut self, id: BitId, val: Option<bool>) {
+        self.as_mut_slice().set(id, val)
+    }
+}
+
+/// Set of lines which should be high
+///
+/// Present entries are treated as `Some(true)` while absent ones are `None`,
+/// so only the listed lines are driven high and all others left untouched.
+/// Entries identify lines by position in request (like indexes of arrays).
+impl AsValues for BTreeSet<LineId> {
+    fn bits(&self) -> BitId {
+        self.range(..MAX_BITS as LineId)
+            .next_back()
+            .map(|&id| id as BitId + 1)
+            .unwrap_or(0)
+    }
+
+    fn get(&self, id: BitId) -> Option<bool> {
+        self.contains(&(id as _)).then_some(true)
+    }
+}
+
+/// Set of lines which should be high
+///
+/// Present entries are treated as `Some(true)` while absent ones are `None`,
+/// so only the listed lines are driven high and all others left untouched.
+/// Entries identify lines by position in request (like indexes of arrays).
+impl<S: BuildHasher> AsValues for HashSet<LineId, S> {
+    fn bits(&self) -> BitId {
+        self.iter()
+            .filter(|&&id| id < MAX_BITS as _)
+            .max()
+            .map(|&id| id as BitId + 1)
+            .unwrap_or(0)
+    }
+
+    fn get(&self, id: BitId) -> Option<bool> {
+        self.contains(&(id as _)).then_some(true)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -509,7 +1052,11 @@ mod test {
 
         assert_eq!(format!("{:#08b}", 0b1000u8), "0b001000");
 
-        //assert_eq!(format!("{:#08b}", Masked::from(0b1000u8)), "0b001000");
+        assert_eq!(format!("{:#08b}", Masked::from(0b1000u8)), "0b001000");
+
+        assert_eq!(format!("{:#1}", Masked::from(1u8)), "0b1");
+
+        assert_eq!(format!("{:#8}", Masked::from(0b1000u8)), "  0b1000");
 
         assert_eq!(format!("{:11}", Masked::from(0b1000u8)), "       1000");
 
@@ -597,4 +1144,396 @@ mod test {
 
         assert!("0b10xy".parse::<Values>().is_err());
     }
+
+    #[test]
+    fn parse_masked_radix() {
+        assert_eq!(
+            "0xff".parse::<Masked<u8>>().unwrap(),
+            Masked {
+                bits: 0xff,
+                mask: 0xff,
+            }
+        );
+
+        assert_eq!(
+            "0d255".parse::<Masked<u8>>().unwrap(),
+            Masked {
+                bits: 255,
+                mask: 0xff,
+            }
+        );
+
+        assert_eq!(
+            "0x1F".parse::<Values>().unwrap(),
+            Values {
+                bits: 0x1f,
+                mask: Bits::MAX,
+            }
+        );
+
+        assert_eq!(
+            "10x1".parse::<Values>().unwrap(),
+            Values {
+                bits: 0b1001,
+                mask: 0b1101,
+            }
+        );
+
+        // unprefixed numbers are binary regardless of digits
+        assert_eq!(
+            "10".parse::<Values>().unwrap(),
+            Values {
+                bits: 0b10,
+                mask: 0b11,
+            }
+        );
+        assert!("12".parse::<Values>().is_err());
+        assert!("255".parse::<Values>().is_err());
+
+        let message = |s: &str| s.parse::<Masked<u8>>().unwrap_err().to_string();
+
+        assert_eq!(message("0x100"), "Line values out of range");
+        assert_eq!(message("0d256"), "Line values out of range");
+        assert_eq!(message("0x"), "Invalid line values number");
+        assert_eq!(message("0xzz"), "Invalid line values number");
+        assert_eq!(message("0x0xff"), "Invalid line values number");
+        assert_eq!(message("0x+1"), "Invalid line values number");
+        assert_eq!(message("0d-1"), "Invalid line values number");
+        assert!("+1".parse::<Values>().is_err());
+    }
+
+    #[test]
+    fn parse_masked_line_order() {
+        assert_eq!(
+            Values::from_line_order("0110").unwrap(),
+            Values {
+                bits: 0b0110,
+                mask: 0b1111,
+            }
+        );
+
+        assert_eq!(
+            Values::from_line_order("100").unwrap(),
+            Values {
+                bits: 0b001,
+                mask: 0b111,
+            }
+        );
+
+        assert_eq!(
+            Values::from_line_order("1x10x").unwrap(),
+            Values {
+                bits: 0b00101,
+                mask: 0b01101,
+            }
+        );
+
+        assert_eq!(Values::from_line_order("").unwrap(), Values::default());
+
+        assert!(Values::from_line_order(&"1".repeat(65)).is_err());
+
+        assert!(Values::from_line_order("0b101").is_err());
+    }
+
+    #[test]
+    fn convert_bool_vec() {
+        assert_eq!(Values::from(Vec::<bool>::new()), Values::default());
+        assert_eq!(Vec::<bool>::from(Values::default()), Vec::<bool>::new());
+
+        assert_eq!(
+            Values::from(vec![true, false, true]),
+            Values {
+                bits: 0b101,
+                mask: 0b111,
+            }
+        );
+
+        let values = Values {
+            bits: 0b1001,
+            mask: 0b1011,
+        };
+
+        assert_eq!(Vec::<bool>::from(values), vec![true, false, false, true]);
+        assert_eq!(
+            Vec::<Option<bool>>::from(values),
+            vec![Some(true), Some(false), None, Some(true)]
+        );
+
+        let full = vec![true; MAX_VALUES];
+
+        assert_eq!(
+            Values::from(full.clone()),
+            Values {
+                bits: Bits::MAX,
+                mask: Bits::MAX,
+            }
+        );
+        assert_eq!(Vec::<bool>::from(Values::from(full.clone())), full);
+
+        assert_eq!(
+            Masked::<u8>::from(vec![true; 10]),
+            Masked::<u8> {
+                bits: u8::MAX,
+                mask: u8::MAX,
+            }
+        );
+    }
+
+    #[test]
+    fn width_values() {
+        let values = Values {
+            bits: 0b1010_0101,
+            mask: 0b1111_0011,
+        };
+
+        let three = values.with_width(3);
+
+        assert_eq!(three.bits(), 3);
+        assert_eq!(three.get(2), None);
+        assert_eq!(three.get(4), None);
+        assert_eq!(format!("{}", three), "x01");
+        assert_eq!(format!("{:#b}", three), "0bx01");
+        assert_eq!(format!("{:>5}", three), "  x01");
+        assert_eq!(
+            three.values(),
+            Values {
+                bits: 0b001,
+                mask: 0b011,
+            }
+        );
+
+        let other = Values {
+            bits: 0b0000_0001,
+            mask: 0b0000_1011,
+        };
+
+        assert_ne!(values, other);
+        assert_eq!(three, other.with_width(3));
+        assert_ne!(three, other.with_width(4));
+
+        assert_eq!(values.with_width(100).width(), MAX_BITS);
+        assert_eq!(Masked::<u8>::default().with_width(100).width(), 8);
+    }
+
+    #[test]
+    fn reverse_bits() {
+        let values = Values {
+            bits: 0b1011_0001,
+            mask: 0b1111_0111,
+        };
+
+        assert_eq!(
+            values.reverse_bits(4),
+            Values {
+                bits: 0b1000,
+                mask: 0b1110,
+            }
+        );
+
+        assert_eq!(
+            values.reverse_bits(5),
+            Values {
+                bits: 0b10001,
+                mask: 0b11101,
+            }
+        );
+
+        assert_eq!(
+            values.reverse_bits(5).reverse_bits(5),
+            values.with_width(5).values()
+        );
+        assert_eq!(values.reverse_bits(0), Values::default());
+        assert_eq!(
+            Masked::<u8>::from(0b0000_0011).reverse_bits(8),
+            Masked::<u8>::from(0b1100_0000)
+        );
+    }
+
+    #[test]
+    fn line_sets() {
+        let set = BTreeSet::from([1, 3]);
+
+        assert_eq!(set.bits(), 4);
+        assert_eq!(AsValues::get(&set, 0), None);
+        assert_eq!(AsValues::get(&set, 1), Some(true));
+        assert_eq!(AsValues::get(&set, 2), None);
+        assert_eq!(AsValues::get(&set, 3), Some(true));
+        assert_eq!(AsValues::get(&set, 4), None);
+
+        let values: Values = set.convert();
+        assert_eq!(values.bits, 0b1010);
+        assert_eq!(values.mask, 0b1010);
+
+        let set = HashSet::<LineId>::from([0, 2, 100]);
+
+        assert_eq!(set.bits(), 3);
+        assert_eq!(
+            set.convert::<Values>(),
+            Values {
+                bits: 0b101,
+                mask: 0b101
+            }
+        );
+
+        assert_eq!(BTreeSet::<LineId>::new().bits(), 0);
+    }
+
+    #[test]
+    fn out_of_range_bits() {
+        let mut values = Values::from(Bits::MAX);
+
+        assert_eq!(values.get(63), Some(true));
+        assert_eq!(values.get(64), None);
+
+        values.set(64, Some(false));
+        assert_eq!(values, Values::from(Bits::MAX));
+
+        let mut bits = 0u8;
+        bits.set(8, Some(true));
+        assert_eq!(bits, 0);
+        assert_eq!(bits.get(8), None);
+    }
+
+    #[test]
+    fn packed_bytes() {
+        let bytes = [0b1000_0001u8, 0b0000_0010];
+
+        assert_eq!(bytes.bits(), 16);
+        assert_eq!(AsValues::get(&bytes, 0), Some(true));
+        assert_eq!(AsValues::get(&bytes, 7), Some(true));
+        assert_eq!(AsValues::get(&bytes, 8), Some(false));
+        assert_eq!(AsValues::get(&bytes, 9), Some(true));
+        assert_eq!(AsValues::get(&bytes, 16), None);
+
+        let values: Values = bytes.convert();
+        assert_eq!(values, Masked::from(0b0000_0010_1000_0001u16).convert());
+
+        let mut vec = vec![0u8; 2];
+        values.copy_into(&mut vec);
+        assert_eq!(vec, bytes);
+
+        vec.set(16, Some(true));
+        vec.set(9, Some(false));
+        vec.set(1, Some(true));
+        assert_eq!(vec, [0b1000_0011, 0]);
+
+        let long = [0xffu8; 9];
+        assert_eq!(long.bits(), MAX_BITS);
+        assert_eq!(AsValues::get(long.as_slice(), MAX_BITS), None);
+    }
+
+    #[test]
+    fn masked_ops() {
+        let m = |s: &str| s.parse::<Masked<u8>>().unwrap();
+
+        let a = m("xx10x1");
+        let b = m("x01x10");
+        let c = m("1xxxxx");
+
+        // overlapping masks
+        assert_eq!(a | b, m("x01011"));
+        assert_eq!(a & b, m("xx1xx0"));
+        assert_eq!(a ^ b, m("x00011"));
+        assert_eq!(a.merge(b), m("x01010"));
+        assert_eq!(b.merge(a), m("x01011"));
+
+        // disjoint masks
+        assert_eq!(a | c, m("1x10x1"));
+        assert_eq!(a & c, Masked::default());
+        assert_eq!(a ^ c, m("1x10x1"));
+        assert_eq!(a.merge(c), m("1x10x1"));
+
+        assert_eq!(!a, m("xx01x0"));
+        assert_eq!(u8::from(!Masked::from(0b0010u8)), 0b1111_1101);
+        assert_eq!(
+            u8::from(Masked::from(0b0100u8) | Masked::from(0b0010u8)),
+            0b0110
+        );
+    }
+
+    #[test]
+    fn values_from_pairs() {
+        let values = Values::from_pairs(&[(2, true), (5, false)]);
+        assert_eq!(values.mask, 0b100100);
+        assert_eq!(values.bits, 0b000100);
+
+        let pairs: &[(BitId, bool)] = &[(0, true), (9, true)];
+        assert_eq!(Masked::<u8>::from(pairs), Masked { bits: 1, mask: 1 });
+
+        let mut values: Values = "x11".parse().unwrap();
+        values.set_pairs(&[(0, false), (3, true)]);
+        assert_eq!(values, "1x10".parse().unwrap());
+    }
+
+    #[test]
+    fn iter_masked() {
+        let values: Values = "1xx0x1x".parse().unwrap();
+
+        assert_eq!(
+            values.into_iter().collect::<Vec<_>>(),
+            [None, Some(true), None, Some(false), None, None, Some(true)]
+        );
+        assert_eq!((&values).into_iter().len(), 7);
+
+        let mut count = 0;
+        for value in values {
+            if value.is_some() {
+                count += 1;
+            }
+        }
+        assert_eq!(count, 3);
+
+        let values: Masked<u8> = "0b10000000".parse().unwrap();
+        assert_eq!(values.into_iter().count(), 8);
+
+        assert_eq!(Masked::<u16>::default().into_iter().next(), None);
+    }
+
+    #[test]
+    fn count_values() {
+        let values: Masked<u8> = "1x0x11".parse().unwrap();
+        assert_eq!(values.count_high(), 3);
+        assert_eq!(values.count_masked(), 4);
+        assert!(values.any_high());
+        assert!(!values.all_high());
+
+        let values: Masked<u8> = "1xx1".parse().unwrap();
+        assert_eq!(values.count_high(), 2);
+        assert_eq!(values.count_masked(), 2);
+        assert!(values.any_high());
+        assert!(values.all_high());
+
+        let values = Masked::<u8>::default();
+        assert_eq!(values.count_high(), 0);
+        assert_eq!(values.count_masked(), 0);
+        assert!(!values.any_high());
+        assert!(values.all_high());
+
+        let values: &[bool] = &[true, false, true];
+        assert_eq!(values.count_high(), 2);
+        assert_eq!(values.count_masked(), 3);
+        assert!(values.any_high());
+        assert!(!values.all_high());
+
+        let values: &[bool] = &[true, true];
+        assert!(values.all_high());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_masked() {
+        let values: Masked<u8> = "10xx01".parse().unwrap();
+
+        let json = serde_json::to_string(&values).unwrap();
+        assert_eq!(json, r#""10xx01""#);
+        assert_eq!(serde_json::from_str::<Masked<u8>>(&json).unwrap(), values);
+
+        let values: Values = "0b0x10".parse().unwrap();
+
+        let json = serde_json::to_string(&values).unwrap();
+        assert_eq!(json, r#""0b0x10""#);
+        assert_eq!(serde_json::from_str::<Values>(&json).unwrap(), values);
+
+        assert!(serde_json::from_str::<Masked<u8>>(r#""10z""#).is_err());
+    }
 }
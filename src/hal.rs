@@ -0,0 +1,200 @@
+use crate::{GpioInputs, GpioLines, GpioOutputs, Input, LineId, Lines, Output, Result};
+use embedded_hal::digital;
+use std::{fmt, marker::PhantomData};
+
+/// Single GPIO line adapter for [embedded_hal] digital traits
+///
+/// Implements [digital::InputPin] for inputs and [digital::OutputPin] with
+/// [digital::StatefulOutputPin] for outputs, so the line can be passed to drivers written
+/// against `embedded-hal`. The lines should be requested with single line only.
+///
+/// Any implementation of [GpioLines] can be used as lines, so drivers can be tested
+/// using simulated lines.
+pub struct Pin<Direction, L = Lines<Direction>> {
+    dir: PhantomData<Direction>,
+    lines: L,
+}
+
+impl<L: GpioLines> Pin<L::Direction, L> {
+    /// Create adapter using requested lines
+    ///
+    /// Fails when lines consists of more than one line.
+    pub fn new(lines: L) -> Result<Self> {
+        if lines.lines().len() != 1 {
+            return Err(crate::invalid_input("Single line expected"));
+        }
+
+        Ok(Self {
+            dir: PhantomData,
+            lines,
+        })
+    }
+
+    /// Get line offset
+    pub fn line(&self) -> LineId {
+        self.lines.lines()[0]
+    }
+
+    /// Get back the lines
+    pub fn into_inner(self) -> L {
+        self.lines
+    }
+
+    fn get(&self) -> Result<bool> {
+        Ok(self.lines.get_values([false])?[0])
+    }
+}
+
+impl<Direction: crate::DirectionType> TryFrom<Lines<Direction>> for Pin<Direction> {
+    type Error = crate::Error;
+
+    fn try_from(lines: Lines<Direction>) -> Result<Self> {
+        Self::new(lines)
+    }
+}
+
+impl<L: GpioOutputs> Pin<Output, L> {
+    fn set(&self, value: bool) -> Result<()> {
+        self.lines.set_values([value])
+    }
+}
+
+/// Error of [Pin] operations
+#[derive(Debug)]
+pub struct PinError(crate::Error);
+
+impl PinError {
+    /// Get underlying GPIO error
+    pub fn into_inner(self) -> crate::Error {
+        self.0
+    }
+}
+
+impl From<crate::Error> for PinError {
+    fn from(error: crate::Error) -> Self {
+        Self(error)
+    }
+}
+
+impl fmt::Display for PinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for PinError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+impl digital::Error for PinError {
+    fn kind(&self) -> digital::ErrorKind {
+        digital::ErrorKind::Other
+    }
+}
+
+impl<Direction, L> digital::ErrorType for Pin<Direction, L> {
+    type Error = PinError;
+}
+
+impl<L: GpioInputs> digital::InputPin for Pin<Input, L> {
+    fn is_high(&mut self) -> core::result::Result<bool, PinError> {
+        Ok(self.get()?)
+    }
+
+    fn is_low(&mut self) -> core::result::Result<bool, PinError> {
+        Ok(!self.get()?)
+    }
+}
+
+impl<L: GpioOutputs> digital::OutputPin for Pin<Output, L> {
+    fn set_low(&mut self) -> core::result::Result<(), PinError> {
+        Ok(self.set(false)?)
+    }
+
+    fn set_high(&mut self) -> core::result::Result<(), PinError> {
+        Ok(self.set(true)?)
+    }
+}
+
+impl<L: GpioOutputs> digital::StatefulOutputPin for Pin<Output, L> {
+    fn is_set_high(&mut self) -> core::result::Result<bool, PinError> {
+        Ok(self.get()?)
+    }
+
+    fn is_set_low(&mut self) -> core::result::Result<bool, PinError> {
+        Ok(!self.get()?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use embedded_hal::digital::Error as _;
+
+    #[test]
+    fn pin_error() {
+        let error = PinError::from(crate::Error::from(std::io::ErrorKind::PermissionDenied));
+
+        assert_eq!(error.kind(), digital::ErrorKind::Other);
+        assert_eq!(
+            error.into_inner().kind(),
+            std::io::ErrorKind::PermissionDenied
+        );
+    }
+
+    #[cfg(feature = "mock")]
+    mod mock {
+        use super::*;
+        use crate::{
+            mock::{MockChip, MockLines},
+            Active, Options,
+        };
+        use embedded_hal::digital::{InputPin, OutputPin, StatefulOutputPin};
+
+        #[test]
+        fn pin_input() {
+            let chip = MockChip::new("gpiochip0", "gpio-mock", 4);
+
+            let lines = chip.request_lines(Options::input([2])).unwrap();
+            let mut pin = Pin::new(lines).unwrap();
+            assert_eq!(pin.line(), 2);
+
+            assert!(pin.is_low().unwrap());
+
+            chip.set_level(2, true).unwrap();
+            assert!(pin.is_high().unwrap());
+            assert!(!pin.is_low().unwrap());
+        }
+
+        #[test]
+        fn pin_output() {
+            let chip = MockChip::new("gpiochip0", "gpio-mock", 4);
+
+            let lines = chip
+                .request_lines(Options::output([1]).active(Active::Low))
+                .unwrap();
+            let mut pin: Pin<Output, MockLines<Output>> = Pin::new(lines).unwrap();
+
+            pin.set_high().unwrap();
+            assert!(pin.is_set_high().unwrap());
+            assert!(!chip.level(1).unwrap());
+
+            pin.toggle().unwrap();
+            assert!(pin.is_set_low().unwrap());
+            assert!(chip.level(1).unwrap());
+
+            pin.set_state(digital::PinState::High).unwrap();
+            assert!(!chip.level(1).unwrap());
+        }
+
+        #[test]
+        fn pin_single_line() {
+            let chip = MockChip::new("gpiochip0", "gpio-mock", 4);
+
+            let lines = chip.request_lines(Options::input([0, 1])).unwrap();
+            assert!(Pin::new(lines).is_err());
+        }
+    }
+}
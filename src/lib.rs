@@ -10,28 +10,55 @@ use std::{
     ops::Deref,
     os::unix::{
         fs::{FileTypeExt, MetadataExt},
-        io::{AsRawFd, FromRawFd},
+        io::{AsRawFd, FromRawFd, IntoRawFd, RawFd},
     },
     path::{Path, PathBuf},
+    time::{Duration, Instant},
 };
 
-use gpiod_core::{invalid_input, major, minor, Internal, Result};
+#[cfg(feature = "v2")]
+use gpiod_core::EventBuffer;
+use gpiod_core::{invalid_input, major, minor, retry_interrupted, select_chip_by_label, Internal};
 
 pub use gpiod_core::{
-    Active, AsValues, AsValuesMut, Bias, BitId, ChipInfo, Direction, DirectionType, Drive, Edge,
-    EdgeDetect, Event, Input, LineId, LineInfo, Masked, Options, Output, Values, ValuesInfo,
-    MAX_BITS, MAX_VALUES,
+    abi_version, sort_chips, AbiVersion, Active, AsValues, AsValuesMut, Bias, BitId, BitOrder,
+    ChipInfo, Direction, DirectionType, Drive, Edge, EdgeDetect, Error, Event, EventClock,
+    EventRecord, EventStats, Input, LineAttribute, LineCapabilities, LineChange, LineChangeKind,
+    LineConfig, LineEventStats, LineId, LineInfo, Masked, MaskedIter, NameMatch, Options, Output,
+    RequestConfig, Result, TimeFormat, Values, ValuesInfo, WidthValues, MAX_BITS, MAX_CONSUMER_LEN,
+    MAX_VALUES,
 };
 
+#[cfg(feature = "embedded-hal")]
+mod hal;
+
+#[cfg(feature = "embedded-hal")]
+pub use hal::{Pin, PinError};
+
+#[cfg(feature = "mock")]
+pub mod mock;
+
+/// Get the version of this crate
+pub fn version() -> &'static str {
+    env!("CARGO_PKG_VERSION")
+}
+
 /// The interface for accessing to the values of GPIO lines
 ///
 /// Use [Chip::request_lines] with [Options::input] or [Options::output] to configure specific
 /// GPIO lines for input or output.
+///
+/// Lines are [Send] and [Sync]. Getting and setting values requires shared reference only,
+/// so lines can be shared between threads via [std::sync::Arc] without any locking.
+/// Reading events requires exclusive reference, so single consumer must own the lines
+/// (or wrap it into a mutex).
 pub struct Lines<Direction> {
     dir: PhantomData<Direction>,
     info: Internal<ValuesInfo>,
     // wrap file to call close on drop
     file: File,
+    #[cfg(feature = "v2")]
+    events: EventBuffer,
 }
 
 impl<Direction> Deref for Lines<Direction> {
@@ -42,16 +69,161 @@ impl<Direction> Deref for Lines<Direction> {
     }
 }
 
+impl<Direction> AsRawFd for Lines<Direction> {
+    fn as_raw_fd(&self) -> RawFd {
+        self.file.as_raw_fd()
+    }
+}
+
+impl<Direction: DirectionType> fmt::Debug for Lines<Direction> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Lines")
+            .field("fd", &self.file.as_raw_fd())
+            .field("config", &self.request_config())
+            .finish()
+    }
+}
+
 impl<Direction: DirectionType> Lines<Direction> {
+    /// Get options which can be used to request the same lines again
+    ///
+    /// The options are reconstructed from the configuration of this request,
+    /// so passing it to [Chip::request_lines] re-acquires lines identically
+    /// (for example after the lines was released due to an error).
+    pub fn options(&self) -> Options<Direction, Vec<LineId>, String> {
+        self.info.options()
+    }
+
+    /// Get snapshot of configuration used to request lines
+    ///
+    /// Unlike [Lines::config] it does not query kernel, so it is cheap enough
+    /// to be used for logging and debugging.
+    pub fn request_config(&self) -> RequestConfig {
+        self.info.request_config::<Direction>()
+    }
+
+    /// Get actual configuration of requested lines
+    ///
+    /// The info of lines is re-read from chip, so it reflects flags which was really applied
+    /// by kernel (for example bias can be ignored for lines which does not support it).
+    /// The chip descriptor duplicated on request is used, so the chip is not re-opened.
+    pub fn config(&self) -> Result<Vec<LineInfo>> {
+        self.info.lines_info()
+    }
+
+    /// Get kernel names of requested lines
+    ///
+    /// The names are in order of requested lines (unnamed lines has empty names).
+    /// The names of all chip lines are read once and cached by the chip.
+    pub fn line_names(&self) -> Result<Vec<String>> {
+        self.info.line_names()
+    }
+
+    /// Change configuration of lines without releasing it
+    ///
+    /// Useful to toggle bias or edge detection at runtime without glitches on outputs.
+    /// The lines should be same as requested, while consumer string is ignored.
+    /// With ABI v1 edge detection and per-line options cannot be reconfigured.
+    pub fn reconfigure(
+        &mut self,
+        options: Options<Direction, impl AsRef<[LineId]>, impl AsRef<str>>,
+    ) -> Result<()> {
+        self.info.reconfigure(self.file.as_raw_fd(), &options)
+    }
+
+    /// Release lines explicitly
+    ///
+    /// Unlike dropping lines it reports failure of closing the request. After successful
+    /// release the lines can be requested again immediately (ex. with different options).
+    pub fn release(self) -> Result<()> {
+        let Self { info, file, .. } = self;
+
+        let result = gpiod_core::close_fd(file.into_raw_fd());
+
+        drop(info);
+
+        result
+    }
+
+    /// Wait until lines have something to read
+    ///
+    /// For inputs requested with edge detection this means that at least one event can be read
+    /// using [Lines::read_event] without blocking. Outputs and inputs without edge detection
+    /// have no event source, so waiting on them simply times out.
+    ///
+    /// Returns `false` when timeout elapsed. The `None` timeout means waiting infinitely.
+    pub fn wait_readable(&self, timeout: Option<Duration>) -> Result<bool> {
+        gpiod_core::poll_readable(self.file.as_raw_fd(), timeout)
+    }
+
+    /// Enable or disable non-blocking mode
+    ///
+    /// Lines are requested in blocking mode, so [Lines::read_event] waits until event arrives.
+    /// In non-blocking mode reading returns [std::io::ErrorKind::WouldBlock] error when no
+    /// events available, which is useful for polling the fd (see [AsRawFd]) in own event loop
+    /// or together with [Lines::wait_readable].
+    pub fn set_nonblocking(&self, nonblocking: bool) -> Result<()> {
+        gpiod_core::set_nonblocking(self.file.as_raw_fd(), nonblocking)
+    }
+
     /// Get the value of GPIO lines
     ///
     /// The values can only be read if the lines have previously been requested as inputs
     /// or outputs using the [Chip::request_lines] method with [Options::input] or with
     /// [Options::output].
+    ///
+    /// Only the values which fits into given buffer are read, while the values in buffer
+    /// above the number of requested lines are cleared (reset to `false` for booleans).
     pub fn get_values<T: AsValuesMut>(&self, mut values: T) -> Result<T> {
         self.info.get_values(self.file.as_raw_fd(), &mut values)?;
         Ok(values)
     }
+
+    /// Get the value of GPIO lines into given buffer
+    ///
+    /// Same as [Lines::get_values] but fills the buffer in place, so it can be reused
+    /// across reads without moving it.
+    ///
+    /// ```no_run
+    /// use gpiod::{Chip, Options};
+    ///
+    /// # fn main() -> gpiod::Result<()> {
+    /// let chip = Chip::new("gpiochip0")?;
+    /// let inputs = chip.request_lines(Options::input([3, 5]))?;
+    ///
+    /// let mut values = vec![false; 2];
+    ///
+    /// for _ in 0..10 {
+    ///     inputs.get_values_into(&mut values)?;
+    ///     println!("values: {:?}", values);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_values_into(&self, values: &mut impl AsValuesMut) -> Result<()> {
+        self.info.get_values(self.file.as_raw_fd(), values)
+    }
+
+    /// Get the value of single GPIO line
+    ///
+    /// The line is given by offset and should be one of requested lines.
+    ///
+    /// ```no_run
+    /// use gpiod::{Chip, Options};
+    ///
+    /// # fn main() -> gpiod::Result<()> {
+    /// let chip = Chip::new("gpiochip0")?;
+    /// let inputs = chip.request_lines(Options::input([3, 5]))?;
+    ///
+    /// if inputs.get_value(5)? {
+    ///     println!("line 5 is active");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_value(&self, line: LineId) -> Result<bool> {
+        self.info.get_value(self.file.as_raw_fd(), line)
+    }
 }
 
 impl Lines<Input> {
@@ -62,17 +234,248 @@ impl Lines<Input> {
     pub fn read_event(&mut self) -> Result<Event> {
         #[cfg(not(feature = "v2"))]
         {
-            todo!();
+            let fd = self.file.as_raw_fd();
+
+            loop {
+                match self.info.read_event(fd) {
+                    Err(error)
+                        if error.kind() == std::io::ErrorKind::WouldBlock
+                            && !gpiod_core::is_nonblocking(fd)? =>
+                    {
+                        // Wait events from any of lines
+                        self.wait_readable(None)?;
+                    }
+                    result => return result,
+                }
+            }
+        }
+
+        #[cfg(feature = "v2")]
+        {
+            // Partially read event is kept in buffer until the rest of it arrives
+            let mut events = Vec::with_capacity(1);
+
+            self.read_events(&mut events, 1)?;
+
+            events
+                .pop()
+                .ok_or_else(|| std::io::ErrorKind::UnexpectedEof.into())
+        }
+    }
+
+    /// Read up to `max` GPIO events at once appending it to `buf`
+    ///
+    /// Waits until at least one event available and returns the number of events which was read.
+    /// With ABI v2 events are read using single syscall which reduces overhead under load.
+    pub fn read_events(&mut self, buf: &mut Vec<Event>, max: usize) -> Result<usize> {
+        #[cfg(not(feature = "v2"))]
+        {
+            let start = buf.len();
+
+            buf.push(self.read_event()?);
+
+            while buf.len() - start < max && self.wait_readable(Some(Duration::ZERO))? {
+                buf.push(self.read_event()?);
+            }
+
+            Ok(buf.len() - start)
         }
 
         #[cfg(feature = "v2")]
         {
-            let mut event = gpiod_core::RawEvent::default();
+            let mut data = vec![0; self.events.read_len(max)];
+
+            loop {
+                let len = retry_interrupted(|| Ok(self.file.read(&mut data)?))?;
+                let count = self.events.decode(
+                    &data[..len],
+                    self.info.index(),
+                    self.info.event_clock(),
+                    buf,
+                )?;
+
+                if count > 0 || len == 0 {
+                    return Ok(count);
+                }
+            }
+        }
+    }
+
+    /// Read GPIO event waiting up to timeout
+    ///
+    /// Returns `None` when timeout elapsed before any event arrived.
+    pub fn read_event_timeout(&mut self, timeout: Duration) -> Result<Option<Event>> {
+        if !self.wait_readable(Some(timeout))? {
+            return Ok(None);
+        }
+
+        self.read_event().map(Some)
+    }
+
+    /// Read GPIO event when available without blocking
+    ///
+    /// Returns `None` when no events pending. Unlike reading in non-blocking mode
+    /// (see [Lines::set_nonblocking]) it does not change the mode of fd, so blocking
+    /// reading (like iterating) still works as usual.
+    pub fn try_read_event(&mut self) -> Result<Option<Event>> {
+        self.read_event_timeout(Duration::ZERO)
+    }
+
+    /// Wait for edge on single line
+    ///
+    /// Reads events discarding ones of other lines or of opposite edge and returns
+    /// the first matching event. Fails when the line is not requested or its edge
+    /// detection does not cover the edge.
+    pub fn wait_edge(&mut self, line: LineId, edge: Edge) -> Result<Event> {
+        self.check_edge(line, edge)?;
+
+        loop {
+            let event = self.read_event()?;
+
+            if event.offset == line && event.edge == edge {
+                return Ok(event);
+            }
+        }
+    }
+
+    /// Wait for edge on single line up to timeout
+    ///
+    /// Same as [Lines::wait_edge] but returns `None` when timeout elapsed before
+    /// matching event arrived.
+    pub fn wait_edge_timeout(
+        &mut self,
+        line: LineId,
+        edge: Edge,
+        timeout: Duration,
+    ) -> Result<Option<Event>> {
+        self.check_edge(line, edge)?;
+
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+
+            let Some(event) = self.read_event_timeout(timeout)? else {
+                return Ok(None);
+            };
+
+            if event.offset == line && event.edge == edge {
+                return Ok(Some(event));
+            }
+        }
+    }
+
+    /// Read GPIO event along with values of all requested lines
+    ///
+    /// The values are read right after the event, so the lines may change in between.
+    /// Useful for protocol decoders which need state of other lines at each edge.
+    pub fn read_event_with_values(&mut self) -> Result<(Event, Values)> {
+        let event = self.read_event()?;
+        let values = self.get_values(Values::default())?;
+
+        Ok((event, values))
+    }
+
+    /// Record GPIO events
+    ///
+    /// Reads events and writes it as JSON lines (see [EventRecord]) until reading or writing fails.
+    /// Available with `json` feature.
+    #[cfg(feature = "json")]
+    pub fn record_events(&mut self, mut writer: impl std::io::Write) -> Result<()> {
+        loop {
+            let record = EventRecord::from(self.read_event()?);
+
+            serde_json::to_writer(&mut writer, &record).map_err(std::io::Error::from)?;
+            writer.write_all(b"\n")?;
+            writer.flush()?;
+        }
+    }
+
+    /// Convert into iterator over batches of GPIO events
+    ///
+    /// Each iteration blocks until at least one event available and then returns up to
+    /// `max_batch` events which already queued by kernel. With ABI v2 whole batch is read using
+    /// single syscall, which reduces overhead under load.
+    pub fn into_batched_iter(self, max_batch: usize) -> BatchedEvents {
+        BatchedEvents {
+            lines: self,
+            max_batch: max_batch.max(1),
+        }
+    }
+
+    /// Read GPIO events collecting per-line statistics
+    ///
+    /// Useful to detect a noisy line which dominates the events of request.
+    pub fn events_with_stats(&mut self) -> EventsWithStats<'_> {
+        let stats = EventStats::new(self.info.lines().len());
+
+        EventsWithStats { lines: self, stats }
+    }
+}
+
+/// Iterator over batches of GPIO events
+///
+/// Use [Lines::into_batched_iter] to create it.
+pub struct BatchedEvents {
+    lines: Lines<Input>,
+    max_batch: usize,
+}
+
+impl BatchedEvents {
+    /// Read batch of GPIO events
+    pub fn read_events(&mut self) -> Result<Vec<Event>> {
+        let mut events = Vec::with_capacity(self.max_batch);
+
+        self.lines.read_events(&mut events, self.max_batch)?;
+
+        Ok(events)
+    }
+
+    /// Get back the lines
+    pub fn into_inner(self) -> Lines<Input> {
+        self.lines
+    }
+}
+
+impl Iterator for BatchedEvents {
+    type Item = Result<Vec<Event>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.read_events())
+    }
+}
+
+/// Iterator over GPIO events which collects per-line statistics
+///
+/// Use [Lines::events_with_stats] to create it.
+pub struct EventsWithStats<'a> {
+    lines: &'a mut Lines<Input>,
+    stats: EventStats,
+}
+
+impl EventsWithStats<'_> {
+    /// Get collected statistics
+    pub fn stats(&self) -> &EventStats {
+        &self.stats
+    }
 
-            gpiod_core::check_size(self.file.read(event.as_mut())?, &event)?;
+    /// Reset collected statistics
+    pub fn reset_stats(&mut self) {
+        self.stats.reset();
+    }
+}
+
+impl Iterator for EventsWithStats<'_> {
+    type Item = Result<Event>;
 
-            event.as_event(self.info.index())
+    fn next(&mut self) -> Option<Self::Item> {
+        let event = self.lines.read_event();
+
+        if let Ok(event) = &event {
+            self.stats.record(event);
         }
+
+        Some(event)
     }
 }
 
@@ -89,19 +492,278 @@ impl Lines<Output> {
     ///
     /// The value can only be set if the lines have previously been requested as outputs
     /// using the [Chip::request_lines] with [Options::output].
+    ///
+    /// New values are applied immediately, there is no need to call [Lines::flush].
     pub fn set_values<T: AsValues>(&self, values: T) -> Result<()> {
         self.info.set_values(self.file.as_raw_fd(), values)
     }
+
+    /// Set the value of single GPIO line
+    ///
+    /// The line is given by offset and should be one of requested lines.
+    /// Other lines of the request are not changed.
+    ///
+    /// ```no_run
+    /// use gpiod::{Chip, Options};
+    ///
+    /// # fn main() -> gpiod::Result<()> {
+    /// let chip = Chip::new("gpiochip0")?;
+    /// let outputs = chip.request_lines(Options::output([3, 5]))?;
+    ///
+    /// outputs.set_value(3, true)?;
+    /// outputs.set_value(5, false)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_value(&self, line: LineId, value: bool) -> Result<()> {
+        self.info.set_value(self.file.as_raw_fd(), line, value)
+    }
+
+    /// Set values of GPIO lines for a given duration and then set inverse values
+    ///
+    /// Only lines which has values in the mask are affected. Inverse values are written even
+    /// when sleep is interrupted.
+    ///
+    /// Timing is best-effort and not real-time guaranteed.
+    pub fn pulse<T: AsValues>(&self, values: T, duration: Duration) -> Result<()> {
+        let fd = self.file.as_raw_fd();
+        let values = values.convert::<Values>();
+
+        self.info.set_values(fd, values)?;
+        let guard = self.info.restore_guard(fd, values)?;
+
+        std::thread::sleep(duration);
+
+        guard.restore()
+    }
+
+    /// Toggle values of all GPIO lines
+    ///
+    /// The current values are read and written back inverted.
+    pub fn toggle(&self) -> Result<()> {
+        self.info.toggle_values(self.file.as_raw_fd(), None)
+    }
+
+    /// Toggle value of single GPIO line
+    ///
+    /// Other lines of the request are not changed.
+    pub fn toggle_line(&self, line: LineId) -> Result<()> {
+        self.info.toggle_values(self.file.as_raw_fd(), Some(line))
+    }
+
+    /// Write a sequence of values to a single GPIO line
+    ///
+    /// Each value is held for `bit_duration` and the line keeps the last value afterwards.
+    /// Other lines of the request are not changed.
+    ///
+    /// Timing is done by sleeping between ioctl calls, so it is subject to scheduling jitter.
+    /// Switching is compensated to avoid accumulating drift, but this is still unsuitable for
+    /// protocols which require precise timing.
+    pub fn write_pattern(
+        &self,
+        line: LineId,
+        pattern: &[bool],
+        bit_duration: Duration,
+    ) -> Result<()> {
+        let id = self.info.index().find(line)?;
+        let start = Instant::now();
+
+        for (n, &value) in pattern.iter().enumerate() {
+            let deadline = start + bit_duration * n as u32;
+            std::thread::sleep(deadline.saturating_duration_since(Instant::now()));
+
+            self.set_values(Values::default().with(id, Some(value)))?;
+        }
+
+        if !pattern.is_empty() {
+            let deadline = start + bit_duration * pattern.len() as u32;
+            std::thread::sleep(deadline.saturating_duration_since(Instant::now()));
+        }
+
+        Ok(())
+    }
+
+    /// Flush pending values
+    ///
+    /// Values are not buffered, each [Lines::set_values] call issues ioctl which changes
+    /// lines immediately, so it is synchronous and this method does nothing.
+    /// It exists for symmetry with buffered IO and will be useful if writes get batched.
+    pub fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Common interface of requested lines
+///
+/// Implemented by [Lines] and by simulated lines of `mock` module, so the logic built on top
+/// of lines can be written generic over it and tested without hardware.
+pub trait GpioLines: Deref<Target = ValuesInfo> {
+    /// Direction of lines
+    type Direction: DirectionType;
+
+    /// Get the value of GPIO lines
+    ///
+    /// See [Lines::get_values].
+    fn get_values<T: AsValuesMut>(&self, values: T) -> Result<T>;
+}
+
+/// Common interface of requested inputs
+pub trait GpioInputs: GpioLines<Direction = Input> {
+    /// Read single GPIO event
+    ///
+    /// See [Lines::read_event].
+    fn read_event(&mut self) -> Result<Event>;
+
+    /// Read single GPIO event when available without blocking
+    ///
+    /// See [Lines::try_read_event].
+    fn try_read_event(&mut self) -> Result<Option<Event>>;
+}
+
+/// Common interface of requested outputs
+pub trait GpioOutputs: GpioLines<Direction = Output> {
+    /// Set the value of GPIO lines
+    ///
+    /// See [Lines::set_values].
+    fn set_values<T: AsValues>(&self, values: T) -> Result<()>;
+}
+
+impl<Direction: DirectionType> GpioLines for Lines<Direction> {
+    type Direction = Direction;
+
+    fn get_values<T: AsValuesMut>(&self, values: T) -> Result<T> {
+        Lines::get_values(self, values)
+    }
+}
+
+impl GpioInputs for Lines<Input> {
+    fn read_event(&mut self) -> Result<Event> {
+        Lines::read_event(self)
+    }
+
+    fn try_read_event(&mut self) -> Result<Option<Event>> {
+        Lines::try_read_event(self)
+    }
+}
+
+impl GpioOutputs for Lines<Output> {
+    fn set_values<T: AsValues>(&self, values: T) -> Result<()> {
+        Lines::set_values(self, values)
+    }
+}
+
+/// Shift register (74HC595-like) driver built on output lines
+///
+/// It drives data, clock and latch lines of the request: each bit is set on data line
+/// and shifted in by rising edge of clock, then latch is pulsed to transfer shifted bits
+/// to register outputs. Use [ShiftOut::new] to create it.
+///
+/// The timing relies on sleeping between ioctl calls, so the actual clock period is longer than
+/// configured and jittery. This is fine for shift registers which have no timing constraints
+/// except minimal pulse widths, but unsuitable for protocols which require precise timing.
+pub struct ShiftOut<'a> {
+    lines: &'a Lines<Output>,
+    data: BitId,
+    clock: BitId,
+    latch: BitId,
+    order: BitOrder,
+    half_period: Duration,
+}
+
+impl<'a> ShiftOut<'a> {
+    /// Create shift register driver using offsets of data, clock and latch lines
+    ///
+    /// All of the lines should be requested as outputs by the given request.
+    pub fn new(
+        lines: &'a Lines<Output>,
+        data: LineId,
+        clock: LineId,
+        latch: LineId,
+    ) -> Result<Self> {
+        let index = lines.info.index();
+
+        Ok(Self {
+            lines,
+            data: index.find(data)?,
+            clock: index.find(clock)?,
+            latch: index.find(latch)?,
+            order: BitOrder::default(),
+            half_period: Duration::ZERO,
+        })
+    }
+
+    /// Configure order of bits when writing bytes (MSB first by default)
+    pub fn bit_order(mut self, order: BitOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Configure minimal duration of clock and latch pulses (zero by default)
+    pub fn half_period(mut self, half_period: Duration) -> Self {
+        self.half_period = half_period;
+        self
+    }
+
+    /// Shift bits in given order and latch it
+    pub fn write_bits(&self, bits: &[bool]) -> Result<()> {
+        for &bit in bits {
+            self.set(
+                Values::default()
+                    .with(self.data, Some(bit))
+                    .with(self.clock, Some(false)),
+            )?;
+            self.set(Values::default().with(self.clock, Some(true)))?;
+        }
+
+        self.set(Values::default().with(self.clock, Some(false)))?;
+        self.set(Values::default().with(self.latch, Some(true)))?;
+        self.set(Values::default().with(self.latch, Some(false)))
+    }
+
+    /// Shift byte and latch it
+    pub fn write_byte(&self, byte: u8) -> Result<()> {
+        self.write_bits(&self.order.bits(byte))
+    }
+
+    /// Shift bytes (for chained registers) and latch it
+    pub fn write_bytes(&self, bytes: &[u8]) -> Result<()> {
+        let bits = bytes
+            .iter()
+            .flat_map(|byte| self.order.bits(*byte))
+            .collect::<Vec<_>>();
+
+        self.write_bits(&bits)
+    }
+
+    fn set(&self, values: Values) -> Result<()> {
+        self.lines.set_values(values)?;
+
+        if !self.half_period.is_zero() {
+            std::thread::sleep(self.half_period);
+        }
+
+        Ok(())
+    }
 }
 
+/// Paths of chips which cannot be opened along with reasons
+///
+/// See [Chip::open_devices] and [Chip::all_with_failures].
+pub type OpenFailures = Vec<(PathBuf, Error)>;
+
 /// A Linux chardev GPIO chip interface
 ///
 /// It can be used to get information about the chip and lines and
 /// to request GPIO lines that can be used as inputs or outputs.
+///
+/// Chip is [Send] and [Sync], all methods requires shared reference only,
+/// so it can be shared between threads via [std::sync::Arc] without any locking.
 pub struct Chip {
     info: Internal<ChipInfo>,
     // wrap file to call close on drop
     file: File,
+    path: PathBuf,
+    rdev: u64,
 }
 
 impl Deref for Chip {
@@ -121,39 +783,148 @@ impl fmt::Display for Chip {
 impl Chip {
     /// Create a new GPIO chip interface using path
     pub fn new(path: impl AsRef<Path>) -> Result<Chip> {
-        let path = path.as_ref();
-
-        #[allow(unused_assignments)]
-        let mut full_path = None;
-
-        let path = if path.starts_with("/dev") {
-            path
-        } else {
-            full_path = Path::new("/dev").join(path).into();
-            full_path.as_ref().unwrap()
-        };
+        Self::open(path.as_ref(), true, true)
+    }
 
-        let file = OpenOptions::new().read(true).write(true).open(path)?;
+    /// Create a GPIO chip interface using path opened for reading only
+    ///
+    /// Useful for monitoring tools which have only read permission on the device
+    /// (ex. due to restrictive udev rules). Chip and line info can be read as usual,
+    /// but requesting outputs fails with [std::io::ErrorKind::PermissionDenied] error.
+    pub fn open_readonly(path: impl AsRef<Path>) -> Result<Chip> {
+        Self::open(path.as_ref(), true, false)
+    }
 
-        Chip::check_device(path)?;
+    /// Open GPIO chip using its label
+    ///
+    /// Unlike chip names the labels (ex. `pinctrl-bcm2711`) are stable across boots.
+    /// Chips which cannot be opened are skipped. Fails when no chips or more than one chip
+    /// has such label.
+    pub fn by_label(label: &str) -> Result<Chip> {
+        let (chips, _) = Self::open_devices(Self::list_devices()?);
 
-        Ok(Chip {
-            info: Internal::<ChipInfo>::from_fd(file.as_raw_fd())?,
-            file,
-        })
+        select_chip_by_label(chips, label)
+    }
+
+    /// Create a new GPIO chip interface using chip number
+    ///
+    /// Opens `/dev/gpiochipN` device, so [ChipInfo::number] of resulting chip equals `number`.
+    pub fn open_by_number(number: u32) -> Result<Chip> {
+        Self::new(format!("gpiochip{}", number))
+    }
+
+    /// Create a new GPIO chip interface using path without checking GPIO subsystem
+    ///
+    /// Unlike [Chip::new] it only checks that path is a character device, but does not check
+    /// that the device belongs to GPIO subsystem using sysfs. This is faster and works
+    /// in minimal containers without `/sys`, but opening a non-GPIO device will fail later
+    /// (or even do something unexpected when device accepts the same ioctl codes),
+    /// so use it only with trusted paths.
+    pub fn open_unchecked(path: impl AsRef<Path>) -> Result<Chip> {
+        Self::open(path.as_ref(), false, true)
+    }
+
+    /// Create a GPIO chip interface using already opened file descriptor
+    ///
+    /// Useful when the process cannot open `/dev` itself, for example when the descriptor
+    /// is passed by supervisor or sandbox. The ownership of descriptor is taken, so it will
+    /// be closed when chip dropped (or when this function fails).
+    ///
+    /// Like [Chip::open_unchecked] it only checks that descriptor refers to a character
+    /// device. The [Chip::path] is derived from the chip name.
+    pub fn from_fd(fd: RawFd) -> Result<Chip> {
+        // take ownership first to close fd on failure
+        let file = unsafe { File::from_raw_fd(fd) };
+        let metadata = file.metadata()?;
+
+        /* Is it a character device? */
+        if !metadata.file_type().is_char_device() {
+            return Err(invalid_input("File is not character device"));
+        }
+
+        let info = Internal::<ChipInfo>::from_fd(file.as_raw_fd())?;
+
+        Ok(Chip {
+            path: Path::new("/dev").join(info.name()),
+            info,
+            file,
+            rdev: metadata.rdev(),
+        })
+    }
+
+    fn open(path: &Path, check_subsystem: bool, write: bool) -> Result<Chip> {
+        #[allow(unused_assignments)]
+        let mut full_path = None;
+
+        let path = if path.starts_with("/dev") {
+            path
+        } else {
+            full_path = Path::new("/dev").join(path).into();
+            full_path.as_ref().unwrap()
+        };
+
+        let file = OpenOptions::new().read(true).write(write).open(path)?;
+
+        let rdev = Chip::check_device(path, check_subsystem)?;
+
+        Ok(Chip {
+            info: Internal::<ChipInfo>::from_fd(file.as_raw_fd())?,
+            file,
+            path: path.to_owned(),
+            rdev,
+        })
     }
 
     /// List all found chips
     pub fn list_devices() -> Result<Vec<PathBuf>> {
         Ok(fs::read_dir("/dev")?
-            .filter_map(Result::ok)
+            .filter_map(|ent| ent.ok())
             .map(|ent| ent.path())
-            .filter(|path| Self::check_device(path).is_ok())
+            .filter(|path| Self::check_device(path, true).is_ok())
             .collect())
     }
 
-    fn check_device(path: &Path) -> Result<()> {
-        let metadata = fs::symlink_metadata(&path)?;
+    /// Open all found chips
+    ///
+    /// Chips which cannot be opened are skipped (use [Chip::all_with_failures] to get
+    /// the reasons). The chips are sorted by number in descending order.
+    pub fn all() -> Result<Vec<Chip>> {
+        Ok(Self::all_with_failures()?.0)
+    }
+
+    /// Open all found chips collecting failures separately
+    ///
+    /// Like [Chip::all] but chips which cannot be opened are returned along with reason.
+    pub fn all_with_failures() -> Result<(Vec<Chip>, OpenFailures)> {
+        let (mut chips, failures) = Self::open_devices(Self::list_devices()?);
+
+        sort_chips(&mut chips);
+
+        Ok((chips, failures))
+    }
+
+    /// Open chips using paths collecting failures separately
+    ///
+    /// Unlike opening chips one by one it does not stop on first failure, so chips which
+    /// cannot be opened (ex. due to permissions) are returned along with reason.
+    pub fn open_devices<P: AsRef<Path>>(
+        paths: impl IntoIterator<Item = P>,
+    ) -> (Vec<Chip>, OpenFailures) {
+        let mut chips = Vec::new();
+        let mut failures = Vec::new();
+
+        for path in paths {
+            match Self::new(&path) {
+                Ok(chip) => chips.push(chip),
+                Err(error) => failures.push((path.as_ref().to_owned(), error)),
+            }
+        }
+
+        (chips, failures)
+    }
+
+    fn check_device(path: &Path, check_subsystem: bool) -> Result<u64> {
+        let metadata = fs::symlink_metadata(path)?;
 
         /* Is it a character device? */
         if !metadata.file_type().is_char_device() {
@@ -162,6 +933,10 @@ impl Chip {
 
         let rdev = metadata.rdev();
 
+        if !check_subsystem {
+            return Ok(rdev);
+        }
+
         /* Is the device associated with the GPIO subsystem? */
         if fs::canonicalize(format!(
             "/sys/dev/char/{}:{}/subsystem",
@@ -172,7 +947,49 @@ impl Chip {
             return Err(invalid_input("Character device is not a GPIO"));
         }
 
-        Ok(())
+        Ok(rdev)
+    }
+
+    /// Get path of opened chip device (ex. `/dev/gpiochip0`)
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Get device number of chip as `(major, minor)` pair
+    pub fn device_number(&self) -> (u64, u64) {
+        (major(self.rdev), minor(self.rdev))
+    }
+
+    /// Probe the version of GPIO ABI supported by kernel
+    ///
+    /// Unlike [ChipInfo::abi] which reports ABI selected at compile time, the kernel is asked,
+    /// so the binary built with `v2` feature can detect that it runs on older kernel which
+    /// supports ABI v1 only. When kernel supports both ABIs [AbiVersion::V2] is reported.
+    /// The chip without lines cannot be used to detect older kernel, so it fails in that case.
+    pub fn abi_version(&self) -> Result<AbiVersion> {
+        self.info.kernel_abi(self.file.as_raw_fd())
+    }
+
+    /// Create another interface to the same GPIO chip
+    ///
+    /// The chip descriptor is duplicated, so the clone refers to the same chip and
+    /// can request lines independently from the original one.
+    pub fn try_clone(&self) -> Result<Chip> {
+        Ok(Chip {
+            info: self.info.clone(),
+            file: self.file.try_clone()?,
+            path: self.path.clone(),
+            rdev: self.rdev,
+        })
+    }
+
+    /// Prepend prefix to consumer of each request made via this chip
+    ///
+    /// Useful to make all lines requested by an application attributable to it.
+    /// The resulting consumer string will be truncated to fit into kernel limit (31 bytes).
+    pub fn with_consumer_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.info.set_consumer_prefix(prefix);
+        self
     }
 
     /// Request the info of a specific GPIO line.
@@ -180,11 +997,149 @@ impl Chip {
         self.info.line_info(self.file.as_raw_fd(), line)
     }
 
+    /// Request the info of all GPIO lines
+    ///
+    /// Fails when info of any line cannot be read, see [Chip::line_info_all_lenient].
+    pub fn line_info_all(&self) -> Result<Vec<LineInfo>> {
+        self.info.line_info_all(self.file.as_raw_fd())
+    }
+
+    /// Request the info of all GPIO lines keeping errors per line
+    ///
+    /// Unlike [Chip::line_info_all] the failure of single line does not abort the sweep.
+    pub fn line_info_all_lenient(&self) -> Vec<Result<LineInfo>> {
+        self.info.line_info_all_lenient(self.file.as_raw_fd())
+    }
+
+    /// Probe features supported by a specific GPIO line
+    ///
+    /// The line must be unused. Features of input lines are probed by short requests which
+    /// released immediately, while output lines is never requested to avoid changing its value,
+    /// so only configured features are reported for it (see [LineCapabilities]).
+    pub fn capabilities(&self, line: LineId) -> Result<LineCapabilities> {
+        self.info.capabilities(self.file.as_raw_fd(), line)
+    }
+
+    /// Iterate over the info of all GPIO lines
+    pub fn line_infos(&self) -> LineInfos<'_> {
+        LineInfos {
+            chip: self,
+            lines: 0..self.num_lines(),
+        }
+    }
+
+    /// Find the offset of GPIO line by name
+    ///
+    /// The names of lines will be scanned at first call and cached, so the first call costs
+    /// one ioctl per line of chip while next calls does not touch the device.
+    /// Returns `None` when chip has no line with such name.
+    pub fn find_line(&self, name: impl AsRef<str>) -> Result<Option<LineId>> {
+        self.info.find_line(self.file.as_raw_fd(), name.as_ref())
+    }
+
+    /// Find the offsets of GPIO lines by names
+    ///
+    /// Same as [Chip::find_line] but for many names at once. The results are in order of names.
+    pub fn find_lines_by_name(&self, names: &[impl AsRef<str>]) -> Result<Vec<Option<LineId>>> {
+        self.info.find_lines_by_name(self.file.as_raw_fd(), names)
+    }
+
+    /// Find GPIO lines which names matches pattern
+    ///
+    /// Unlike [Chip::find_line] returns all matched lines with names ordered by offsets.
+    /// When line names are not unique only the first line with such name is considered.
+    pub fn find_lines(
+        &self,
+        pattern: impl AsRef<str>,
+        mode: NameMatch,
+    ) -> Result<Vec<(LineId, String)>> {
+        self.info
+            .find_lines(self.file.as_raw_fd(), pattern.as_ref(), mode)
+    }
+
+    /// Find GPIO lines which names matches glob pattern (ex. `led*`)
+    ///
+    /// Same as [Chip::find_lines] with [NameMatch::Glob].
+    pub fn find_lines_matching(&self, pattern: impl AsRef<str>) -> Result<Vec<(LineId, String)>> {
+        self.find_lines(pattern, NameMatch::Glob)
+    }
+
+    /// Start watching info changes of a specific GPIO line
+    ///
+    /// Returns current line info. The changes such as requesting, releasing or reconfiguring
+    /// the line by any process can be read using [Chip::read_line_info_change] then.
+    pub fn watch_line_info(&self, line: LineId) -> Result<LineInfo> {
+        self.info.watch_line_info(self.file.as_raw_fd(), line)
+    }
+
+    /// Stop watching info changes of a specific GPIO line
+    pub fn unwatch_line_info(&self, line: LineId) -> Result<()> {
+        self.info.unwatch_line_info(self.file.as_raw_fd(), line)
+    }
+
+    /// Read info change of watched GPIO lines
+    ///
+    /// Waits until any of lines watched using [Chip::watch_line_info] changes.
+    pub fn read_line_info_change(&self) -> Result<LineChange> {
+        let mut change = gpiod_core::RawInfoChange::default();
+
+        gpiod_core::check_size(
+            retry_interrupted(|| Ok((&self.file).read(change.as_mut())?))?,
+            &change,
+        )?;
+
+        change.as_info_change()
+    }
+
+    /// Wait until a specific GPIO line is released
+    ///
+    /// Watches the info changes of line and returns `true` when line is unused or has been
+    /// released by its consumer, so it can likely be requested. Returns `false` when timeout
+    /// elapsed. The `None` timeout means waiting infinitely.
+    ///
+    /// The line must not be watched already. Info changes of other watched lines which
+    /// arrives while waiting will be discarded.
+    pub fn wait_line_free(&self, line: LineId, timeout: Option<Duration>) -> Result<bool> {
+        let fd = self.file.as_raw_fd();
+
+        let result = if self.info.watch_line_info(fd, line)?.used {
+            self.wait_line_released(line, timeout)
+        } else {
+            Ok(true)
+        };
+
+        self.info.unwatch_line_info(fd, line)?;
+
+        result
+    }
+
+    fn wait_line_released(&self, line: LineId, timeout: Option<Duration>) -> Result<bool> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
+        loop {
+            let timeout =
+                deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+
+            if !gpiod_core::poll_readable(self.file.as_raw_fd(), timeout)? {
+                return Ok(false);
+            }
+
+            let change = self.read_line_info_change()?;
+
+            if change.line == line && change.kind == LineChangeKind::Released {
+                return Ok(true);
+            }
+        }
+    }
+
     /// Request the GPIO chip to configure the lines passed as argument as inputs or outputs
     ///
     /// Calling this operation is a precondition to being able to set the state of the GPIO lines.
     /// All the lines passed in one request must share the configured options such as active state,
     /// edge detect, GPIO bias, output drive and consumer string.
+    ///
+    /// Lines created using [Options::input_named] or [Options::output_named] will be resolved
+    /// by names here, the unknown names results in [std::io::ErrorKind::InvalidInput] error.
     pub fn request_lines<Direction: DirectionType>(
         &self,
         options: Options<Direction, impl AsRef<[LineId]>, impl AsRef<str>>,
@@ -197,6 +1152,669 @@ impl Chip {
             dir: PhantomData,
             info,
             file,
+            #[cfg(feature = "v2")]
+            events: Default::default(),
         })
     }
+
+    /// Request each GPIO line separately with its own options
+    ///
+    /// The kernel applies single consumer string per request, so requesting lines individually
+    /// is the only way to label each line distinctly. The lines given to options are ignored.
+    ///
+    /// Note that each request holds its own file descriptor and the returned handles
+    /// are independent, so values of such lines cannot be get or set atomically.
+    pub fn request_lines_individually<Direction: DirectionType>(
+        &self,
+        requests: &[(
+            LineId,
+            Options<Direction, impl AsRef<[LineId]>, impl AsRef<str>>,
+        )],
+    ) -> Result<Vec<Lines<Direction>>> {
+        let mut lines = Vec::with_capacity(requests.len());
+
+        for (line, options) in requests {
+            lines.push(self.request_lines(options.to_owned().lines([*line]))?);
+        }
+
+        Ok(lines)
+    }
+
+    /// Request any number of GPIO lines
+    ///
+    /// Unlike [Chip::request_lines] it is not limited by [MAX_VALUES] lines. The lines are
+    /// split into multiple requests (see [Options::split]) which are handled together.
+    pub fn request_lines_group<Direction: DirectionType>(
+        &self,
+        options: Options<Direction, impl AsRef<[LineId]>, impl AsRef<str>>,
+    ) -> Result<LinesGroup<Direction>> {
+        let parts = options
+            .split()?
+            .into_iter()
+            .map(|options| self.request_lines(options))
+            .collect::<Result<_>>()?;
+
+        Ok(LinesGroup { parts })
+    }
+}
+
+/// The group of GPIO lines which is not limited by number of lines
+///
+/// Use [Chip::request_lines_group] to create it. The values are indexed by position of line
+/// in whole group, so types like `Vec<bool>` should be used to hold it.
+///
+/// Note that the lines are split into requests with at most [MAX_VALUES] lines, so getting
+/// and setting values is atomic within each request only, not across the whole group.
+pub struct LinesGroup<Direction> {
+    parts: Vec<Lines<Direction>>,
+}
+
+impl<Direction> LinesGroup<Direction> {
+    /// Get underlying requests
+    pub fn parts(&self) -> &[Lines<Direction>] {
+        &self.parts
+    }
+
+    /// Get offsets of requested lines
+    pub fn lines(&self) -> Vec<LineId> {
+        self.parts
+            .iter()
+            .flat_map(|part| part.lines().iter().copied())
+            .collect()
+    }
+
+    /// Iterate over parts along with position of first line of each part
+    fn parts_with_base(&self) -> impl Iterator<Item = (BitId, &Lines<Direction>)> {
+        self.parts.iter().scan(0, |base, part| {
+            let part_base = *base;
+            *base += part.lines().len() as BitId;
+            Some((part_base, part))
+        })
+    }
+}
+
+impl<Direction: DirectionType> LinesGroup<Direction> {
+    /// Get the value of GPIO lines
+    ///
+    /// Same as [Lines::get_values] but for all lines of group.
+    pub fn get_values<T: AsValuesMut>(&self, mut values: T) -> Result<T> {
+        for (base, part) in self.parts_with_base() {
+            let mut part_values = Values::default();
+
+            for id in 0..part.lines().len() as BitId {
+                if values.get(base + id).is_some() {
+                    part_values.set(id, Some(false));
+                }
+            }
+
+            part.get_values_into(&mut part_values)?;
+
+            for id in 0..part.lines().len() as BitId {
+                if let Some(value) = part_values.get(id) {
+                    values.set(base + id, Some(value));
+                }
+            }
+        }
+
+        Ok(values)
+    }
+}
+
+impl LinesGroup<Input> {
+    /// Read single GPIO event from any part of group
+    ///
+    /// The [Event::line] is the position of line in whole group.
+    pub fn read_event(&mut self) -> Result<Event> {
+        let fds = self
+            .parts
+            .iter()
+            .map(|part| part.as_raw_fd())
+            .collect::<Vec<_>>();
+
+        let index = loop {
+            if let Some(index) = gpiod_core::poll_readable_any(&fds, None)? {
+                break index;
+            }
+        };
+
+        let base = self.parts[..index]
+            .iter()
+            .map(|part| part.lines().len() as BitId)
+            .sum::<BitId>();
+
+        let mut event = self.parts[index].read_event()?;
+        event.line += base;
+
+        Ok(event)
+    }
+}
+
+impl LinesGroup<Output> {
+    /// Set the value of GPIO lines
+    ///
+    /// Same as [Lines::set_values] but for all lines of group. The parts which has no values
+    /// to set are skipped.
+    pub fn set_values<T: AsValues>(&self, values: T) -> Result<()> {
+        for (base, part) in self.parts_with_base() {
+            let mut part_values = Values::default();
+
+            for id in 0..part.lines().len() as BitId {
+                part_values.set(id, values.get(base + id));
+            }
+
+            if part_values.mask != 0 {
+                part.set_values(part_values)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Iterator over the info of GPIO lines of chip
+///
+/// Use [Chip::line_infos] or iterate over chip reference to create it.
+/// The info of each line is requested lazily, so errors are reported per line.
+pub struct LineInfos<'a> {
+    chip: &'a Chip,
+    lines: core::ops::Range<LineId>,
+}
+
+impl Iterator for LineInfos<'_> {
+    type Item = Result<(LineId, LineInfo)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = self.lines.next()?;
+
+        Some(self.chip.line_info(line).map(|info| (line, info)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.lines.size_hint()
+    }
+}
+
+impl ExactSizeIterator for LineInfos<'_> {}
+
+impl<'a> IntoIterator for &'a Chip {
+    type Item = Result<(LineId, LineInfo)>;
+    type IntoIter = LineInfos<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.line_infos()
+    }
+}
+
+impl TryFrom<&Chip> for Vec<LineInfo> {
+    type Error = Error;
+
+    fn try_from(chip: &Chip) -> Result<Self> {
+        chip.line_infos()
+            .map(|res| res.map(|(_, info)| info))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[cfg(feature = "v2")]
+    #[test]
+    fn read_event_split() {
+        use gpiod_core::RawEvent;
+        use std::{io::Write, os::unix::net::UnixStream};
+
+        let (reader, mut writer) = UnixStream::pair().unwrap();
+        let options = Options::input([3, 5]).edge(EdgeDetect::Both);
+
+        let mut lines = Lines::<Input> {
+            dir: PhantomData,
+            info: Internal::<ValuesInfo>::new("gpiochip0", &options).unwrap(),
+            file: unsafe { File::from_raw_fd(reader.into_raw_fd()) },
+            events: Default::default(),
+        };
+
+        let mut data = Vec::new();
+        for offset in [5, 3] {
+            let mut event = RawEvent::default();
+            event.offset = offset;
+            // rising edge as defined by kernel ABI v2
+            event.id = 1;
+            data.extend_from_slice(event.as_mut());
+        }
+
+        // first event arrives in two parts, so the next read also gets the head of second event
+        writer.write_all(&data[..10]).unwrap();
+        let rest = data[10..].to_vec();
+        let inject = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            writer.write_all(&rest).unwrap();
+            writer
+        });
+
+        let event = lines.read_event().unwrap();
+        assert_eq!(event.offset, 5);
+        assert_eq!(event.edge, Edge::Rising);
+
+        let event = lines.read_event().unwrap();
+        assert_eq!(event.offset, 3);
+
+        inject.join().unwrap();
+    }
+
+    #[cfg(all(feature = "v2", feature = "json"))]
+    #[test]
+    fn record_events_json() {
+        use gpiod_core::RawEvent;
+        use std::{io::Write, os::unix::net::UnixStream};
+
+        let (reader, mut writer) = UnixStream::pair().unwrap();
+        let options = Options::input([3, 5]).edge(EdgeDetect::Both);
+
+        let mut lines = Lines::<Input> {
+            dir: PhantomData,
+            info: Internal::<ValuesInfo>::new("gpiochip0", &options).unwrap(),
+            file: unsafe { File::from_raw_fd(reader.into_raw_fd()) },
+            events: Default::default(),
+        };
+
+        let mut event = RawEvent::default();
+        event.offset = 5;
+        event.timestamp_ns = 1000;
+        // falling edge as defined by kernel ABI v2
+        event.id = 2;
+        event.seqno = 1;
+        event.line_seqno = 1;
+        writer.write_all(event.as_mut()).unwrap();
+        drop(writer);
+
+        let mut output = Vec::new();
+
+        // stops when reading fails
+        assert!(lines.record_events(&mut output).is_err());
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            concat!(
+                r#"{"line_offset":5,"line":1,"edge":"falling","time_ns":1000,"#,
+                r#""clock":"monotonic","seqno":1,"line_seqno":1}"#,
+                "\n"
+            )
+        );
+    }
+
+    #[cfg(feature = "v2")]
+    #[test]
+    fn batched_events_keep_buffered() {
+        use gpiod_core::RawEvent;
+        use std::{io::Write, os::unix::net::UnixStream};
+
+        let (reader, mut writer) = UnixStream::pair().unwrap();
+        let options = Options::input([3, 5]).edge(EdgeDetect::Both);
+
+        let mut lines = Lines::<Input> {
+            dir: PhantomData,
+            info: Internal::<ValuesInfo>::new("gpiochip0", &options).unwrap(),
+            file: unsafe { File::from_raw_fd(reader.into_raw_fd()) },
+            events: Default::default(),
+        };
+
+        let mut data = Vec::new();
+        for (offset, seqno) in [(5, 1), (3, 2), (5, 3)] {
+            let mut event = RawEvent::default();
+            event.offset = offset;
+            // rising edge as defined by kernel ABI v2
+            event.id = 1;
+            event.seqno = seqno;
+            data.extend_from_slice(event.as_mut());
+        }
+
+        // reading the rest of first event leaves the head of second one in buffer
+        writer.write_all(&data[..10]).unwrap();
+        let rest = data[10..].to_vec();
+        let inject = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            writer.write_all(&rest).unwrap();
+            writer
+        });
+
+        assert_eq!(lines.read_event().unwrap().seqno, Some(1));
+
+        let _writer = inject.join().unwrap();
+
+        let events = lines.into_batched_iter(4).next().unwrap().unwrap();
+        assert_eq!(
+            events
+                .iter()
+                .map(|event| (event.offset, event.seqno))
+                .collect::<Vec<_>>(),
+            [(3, Some(2)), (5, Some(3))]
+        );
+    }
+
+    #[cfg(feature = "v2")]
+    #[test]
+    fn wait_edge_injected() {
+        use gpiod_core::RawEvent;
+        use std::{io::Write, os::unix::net::UnixStream};
+
+        // Event ids as defined by kernel ABI v2
+        const RISING_EDGE: u32 = 1;
+        const FALLING_EDGE: u32 = 2;
+
+        let (reader, mut writer) = UnixStream::pair().unwrap();
+        let options = Options::input([3, 5]).edge(EdgeDetect::Both);
+
+        let mut lines = Lines::<Input> {
+            dir: PhantomData,
+            info: Internal::<ValuesInfo>::new("gpiochip0", &options).unwrap(),
+            file: unsafe { File::from_raw_fd(reader.into_raw_fd()) },
+            events: Default::default(),
+        };
+
+        for (offset, id) in [(3, RISING_EDGE), (5, FALLING_EDGE), (5, RISING_EDGE)] {
+            let mut event = RawEvent::default();
+            event.offset = offset;
+            event.id = id;
+            writer.write_all(event.as_mut()).unwrap();
+        }
+
+        let event = lines.wait_edge(5, Edge::Rising).unwrap();
+        assert_eq!(event.offset, 5);
+        assert_eq!(event.line, 1);
+        assert_eq!(event.edge, Edge::Rising);
+
+        let timeout = Duration::from_millis(20);
+        assert!(lines
+            .wait_edge_timeout(3, Edge::Falling, timeout)
+            .unwrap()
+            .is_none());
+
+        let error = lines.wait_edge(7, Edge::Rising).err().unwrap();
+        assert!(matches!(error, Error::LineNotFound(_)));
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn output_unknown_line() {
+        use std::os::unix::net::UnixStream;
+
+        let (reader, _writer) = UnixStream::pair().unwrap();
+        let options = Options::output([3, 5]);
+
+        let lines = Lines::<Output> {
+            dir: PhantomData,
+            info: Internal::<ValuesInfo>::new("gpiochip0", &options).unwrap(),
+            file: unsafe { File::from_raw_fd(reader.into_raw_fd()) },
+            #[cfg(feature = "v2")]
+            events: Default::default(),
+        };
+
+        let error = lines.set_value(4, true).err().unwrap();
+        assert!(matches!(error, Error::LineNotFound(_)));
+
+        let error = lines
+            .write_pattern(4, &[true], Duration::ZERO)
+            .err()
+            .unwrap();
+        assert!(matches!(error, Error::LineNotFound(_)));
+
+        let error = ShiftOut::new(&lines, 3, 5, 4).err().unwrap();
+        assert!(matches!(error, Error::LineNotFound(ref line) if line == "4"));
+    }
+
+    #[cfg(feature = "v2")]
+    #[test]
+    fn try_read_event_injected() {
+        use gpiod_core::RawEvent;
+        use std::{io::Write, os::unix::net::UnixStream};
+
+        fn rising(offset: LineId, seqno: u32) -> RawEvent {
+            let mut event = RawEvent::default();
+            event.offset = offset;
+            // rising edge as defined by kernel ABI v2
+            event.id = 1;
+            event.seqno = seqno;
+            event
+        }
+
+        let (reader, mut writer) = UnixStream::pair().unwrap();
+        let options = Options::input([3, 5]).edge(EdgeDetect::Both);
+
+        let mut lines = Lines::<Input> {
+            dir: PhantomData,
+            info: Internal::<ValuesInfo>::new("gpiochip0", &options).unwrap(),
+            file: unsafe { File::from_raw_fd(reader.into_raw_fd()) },
+            events: Default::default(),
+        };
+
+        assert!(lines.try_read_event().unwrap().is_none());
+        // fd mode is kept as is
+        assert!(!gpiod_core::is_nonblocking(lines.as_raw_fd()).unwrap());
+
+        writer.write_all(rising(3, 1).as_mut()).unwrap();
+
+        let event = lines.try_read_event().unwrap().unwrap();
+        assert_eq!(event.offset, 3);
+        assert_eq!(event.seqno, Some(1));
+        assert!(lines.try_read_event().unwrap().is_none());
+
+        let writing = std::thread::spawn(move || {
+            writer.write_all(rising(5, 2).as_mut()).unwrap();
+            writer
+        });
+
+        let event = lines.read_event().unwrap();
+        assert_eq!(event.offset, 5);
+        assert_eq!(event.line, 1);
+        assert_eq!(event.seqno, Some(2));
+
+        writing.join().unwrap();
+    }
+
+    #[cfg(feature = "v2")]
+    #[test]
+    fn read_events_interrupted() {
+        use gpiod_core::RawEvent;
+        use nix::{
+            sys::{
+                pthread::{pthread_kill, pthread_self},
+                signal::{sigaction, SaFlags, SigAction, SigHandler, SigSet, Signal},
+            },
+            unistd::gettid,
+        };
+        use std::{
+            io::Write,
+            os::unix::net::UnixStream,
+            sync::{
+                atomic::{AtomicUsize, Ordering},
+                mpsc,
+            },
+            thread,
+        };
+
+        static SIGNALS: AtomicUsize = AtomicUsize::new(0);
+
+        extern "C" fn on_signal(_: nix::libc::c_int) {
+            SIGNALS.fetch_add(1, Ordering::SeqCst);
+        }
+
+        // Wait until thread sleeps in blocking call
+        fn wait_sleeping(tid: nix::unistd::Pid) {
+            let path = format!("/proc/self/task/{tid}/stat");
+            loop {
+                let stat = std::fs::read_to_string(&path).unwrap();
+                // state follows the parenthesized command name
+                if stat.rsplit(") ").next().unwrap().starts_with('S') {
+                    return;
+                }
+                thread::yield_now();
+            }
+        }
+
+        let (reader, mut writer) = UnixStream::pair().unwrap();
+        let options = Options::input([3, 7]).edge(EdgeDetect::Both);
+
+        let mut lines = Lines::<Input> {
+            dir: PhantomData,
+            info: Internal::<ValuesInfo>::new("gpiochip0", &options).unwrap(),
+            file: unsafe { File::from_raw_fd(reader.into_raw_fd()) },
+            events: Default::default(),
+        };
+
+        // without SA_RESTART blocking read fails with EINTR
+        let action = SigAction::new(
+            SigHandler::Handler(on_signal),
+            SaFlags::empty(),
+            SigSet::empty(),
+        );
+        let old_action = unsafe { sigaction(Signal::SIGUSR1, &action) }.unwrap();
+
+        let (sender, receiver) = mpsc::channel();
+        let reading = thread::spawn(move || {
+            sender.send((pthread_self(), gettid())).unwrap();
+
+            let mut events = Vec::new();
+            lines.read_events(&mut events, 4).map(|_| events)
+        });
+
+        let (thread, tid) = receiver.recv().unwrap();
+
+        wait_sleeping(tid);
+        pthread_kill(thread, Signal::SIGUSR1).unwrap();
+        while SIGNALS.load(Ordering::SeqCst) == 0 {
+            thread::yield_now();
+        }
+        // read is restarted after the interrupt
+        wait_sleeping(tid);
+
+        let mut event = RawEvent::default();
+        event.offset = 7;
+        // rising edge as defined by kernel ABI v2
+        event.id = 1;
+        writer.write_all(event.as_mut()).unwrap();
+
+        let result = reading.join().unwrap();
+        unsafe { sigaction(Signal::SIGUSR1, &old_action) }.unwrap();
+
+        assert_eq!(SIGNALS.load(Ordering::SeqCst), 1);
+
+        let events = result.unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].offset, 7);
+        assert_eq!(events[0].line, 1);
+        assert_eq!(events[0].edge, Edge::Rising);
+    }
+
+    #[test]
+    fn chip_send_sync() {
+        assert_send_sync::<Chip>();
+    }
+
+    #[test]
+    #[ignore = "requires GPIO chips"]
+    fn all_chips() {
+        let chips = Chip::all().unwrap();
+        let (opened, _) = Chip::open_devices(Chip::list_devices().unwrap());
+
+        assert_eq!(chips.len(), opened.len());
+        assert!(chips
+            .windows(2)
+            .all(|pair| pair[0].number() >= pair[1].number()));
+    }
+
+    #[test]
+    fn open_devices_failures() {
+        let paths = ["/dev/null", "/nonexistent/gpiochip9"];
+
+        let (chips, failures) = Chip::open_devices(paths);
+
+        assert!(chips.is_empty());
+        assert_eq!(
+            failures
+                .iter()
+                .map(|(path, _)| path.to_str().unwrap())
+                .collect::<Vec<_>>(),
+            paths
+        );
+        assert_eq!(failures[1].1.kind(), std::io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn lines_debug_config() {
+        let options = Options::output([4]).consumer("led").values([true]);
+
+        let lines = Lines::<Output> {
+            dir: PhantomData,
+            info: Internal::<ValuesInfo>::new("gpiochip0", &options).unwrap(),
+            file: File::open("/dev/null").unwrap(),
+            #[cfg(feature = "v2")]
+            events: Default::default(),
+        };
+
+        let config = lines.request_config();
+        assert_eq!(config.direction, Direction::Output);
+        assert_eq!(config.lines, [4]);
+        assert_eq!(config.consumer, "led");
+        assert_eq!(config.values, Some(Values { bits: 1, mask: 1 }));
+
+        let debug = format!("{lines:?}");
+        assert!(debug.starts_with("Lines {"));
+        assert!(debug.contains("consumer: \"led\""));
+    }
+
+    #[test]
+    #[ignore = "requires GPIO chip readable by user"]
+    fn chip_open_readonly() {
+        let path = Chip::list_devices().unwrap().into_iter().next().unwrap();
+        let chip = Chip::open_readonly(path).unwrap();
+
+        chip.line_info(0).unwrap();
+
+        let error = chip.request_lines(Options::output([0])).err().unwrap();
+        assert_eq!(error.kind(), std::io::ErrorKind::PermissionDenied);
+    }
+
+    #[test]
+    fn chip_from_fd_not_device() {
+        let file = File::open("/dev/null").unwrap();
+        let (reader, _writer) = std::os::unix::net::UnixStream::pair().unwrap();
+
+        let error = Chip::from_fd(reader.into_raw_fd()).err().unwrap();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+
+        // character device which is not a GPIO chip
+        assert!(Chip::from_fd(file.into_raw_fd()).is_err());
+    }
+
+    #[test]
+    #[ignore = "requires GPIO chip with free line 0"]
+    fn lines_config_direction() {
+        let chip = Chip::new("gpiochip0").unwrap();
+        let inputs = chip.request_lines(Options::input([0])).unwrap();
+
+        let config = inputs.config().unwrap();
+        assert_eq!(config.len(), 1);
+        assert_eq!(config[0].direction, Direction::Input);
+    }
+
+    #[test]
+    #[ignore = "requires GPIO chip with free lines 0 and 1"]
+    fn lines_names_order() {
+        let chip = Chip::new("gpiochip0").unwrap();
+        let inputs = chip.request_lines(Options::input([1, 0])).unwrap();
+
+        let names = inputs.line_names().unwrap();
+        assert_eq!(names[0], chip.line_info(1).unwrap().name);
+        assert_eq!(names[1], chip.line_info(0).unwrap().name);
+    }
+
+    #[test]
+    fn lines_send_sync() {
+        assert_send_sync::<Lines<Input>>();
+        assert_send_sync::<Lines<Output>>();
+    }
 }
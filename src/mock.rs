@@ -0,0 +1,546 @@
+//! Software simulation of GPIO chip
+//!
+//! Useful to test logic built on top of GPIO lines without hardware. The [MockChip] keeps
+//! levels of lines in memory: values set to outputs can be read back and levels of inputs
+//! can be changed to inject edge events.
+//!
+//! Both [MockLines] and [crate::Lines] implements [GpioLines] with [GpioInputs] or
+//! [GpioOutputs], so code written generic over these traits works with either.
+//!
+//! ```
+//! use gpiod::{mock::MockChip, EdgeDetect, Options};
+//!
+//! # fn main() -> gpiod::Result<()> {
+//! let chip = MockChip::new("gpiochip0", "gpio-mock", 8);
+//!
+//! let output = chip.request_lines(Options::output([2]))?;
+//! let mut input = chip.request_lines(Options::input([5]).edge(EdgeDetect::Both))?;
+//!
+//! output.set_values([true])?;
+//! assert!(chip.level(2)?);
+//!
+//! output.set_values([false])?;
+//! assert!(!chip.level(2)?);
+//!
+//! // simulate external signal
+//! chip.set_level(5, true)?;
+//! assert_eq!(input.get_values([false])?, [true]);
+//!
+//! let event = input.read_event()?;
+//! assert_eq!(event.offset, 5);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+    AsValues, AsValuesMut, BitId, ChipInfo, DirectionType, Edge, EdgeDetect, Error, Event,
+    GpioInputs, GpioLines, GpioOutputs, Input, Internal, LineId, Options, Output, Result,
+    ValuesInfo,
+};
+use std::{
+    collections::VecDeque,
+    marker::PhantomData,
+    ops::Deref,
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    time::Instant,
+};
+
+struct MockRequest {
+    id: u64,
+    lines: Vec<LineId>,
+    active_low: bool,
+    edge: EdgeDetect,
+    events: VecDeque<Event>,
+    seqno: u32,
+    line_seqno: Vec<u32>,
+}
+
+struct MockState {
+    levels: Vec<bool>,
+    requested: Vec<bool>,
+    requests: Vec<MockRequest>,
+    next_id: u64,
+}
+
+struct MockShared {
+    state: Mutex<MockState>,
+    ready: Condvar,
+    start: Instant,
+}
+
+impl MockShared {
+    fn lock(&self) -> MutexGuard<'_, MockState> {
+        // The state is always consistent so poisoning can be ignored
+        self.state.lock().unwrap_or_else(|error| error.into_inner())
+    }
+}
+
+/// Simulated GPIO chip
+///
+/// Clones of chip shares the same lines. Chip info is accessible via [Deref] like for
+/// [crate::Chip], so helpers like [crate::sort_chips] works with simulated chips too.
+#[derive(Clone)]
+pub struct MockChip {
+    info: Internal<ChipInfo>,
+    shared: Arc<MockShared>,
+}
+
+impl Deref for MockChip {
+    type Target = ChipInfo;
+
+    fn deref(&self) -> &Self::Target {
+        &self.info
+    }
+}
+
+impl MockChip {
+    /// Create simulated chip with given number of lines
+    ///
+    /// All lines initially has low level.
+    pub fn new(name: impl Into<String>, label: impl Into<String>, num_lines: LineId) -> Self {
+        Self {
+            info: Internal::<ChipInfo>::new(name, label, num_lines),
+            shared: Arc::new(MockShared {
+                state: Mutex::new(MockState {
+                    levels: vec![false; num_lines as usize],
+                    requested: vec![false; num_lines as usize],
+                    requests: Vec::new(),
+                    next_id: 0,
+                }),
+                ready: Condvar::new(),
+                start: Instant::now(),
+            }),
+        }
+    }
+
+    /// Request the simulated lines
+    ///
+    /// Lines should be given by offsets, per-line options are ignored.
+    pub fn request_lines<Direction: DirectionType>(
+        &self,
+        options: Options<Direction, impl AsRef<[LineId]>, impl AsRef<str>>,
+    ) -> Result<MockLines<Direction>> {
+        options.validate()?;
+
+        let info = Internal::<ValuesInfo>::new(self.name(), &options)?;
+        let lines = info.lines().to_owned();
+        let active_low = matches!(info.active(), crate::Active::Low);
+
+        let mut state = self.shared.lock();
+
+        for &line in &lines {
+            if line as usize >= state.levels.len() {
+                return Err(Error::LineNotFound(line.to_string()));
+            }
+        }
+
+        if lines.iter().any(|&line| state.requested[line as usize]) {
+            return Err(Error::LineBusy(std::io::Error::new(
+                std::io::ErrorKind::ResourceBusy,
+                "Lines are busy",
+            )));
+        }
+
+        for &line in &lines {
+            state.requested[line as usize] = true;
+        }
+
+        if let Some(values) = info.values() {
+            for (id, &line) in lines.iter().enumerate() {
+                if let Some(value) = values.get(id as _) {
+                    state.levels[line as usize] = value != active_low;
+                }
+            }
+        }
+
+        let id = state.next_id;
+        state.next_id += 1;
+
+        state.requests.push(MockRequest {
+            id,
+            line_seqno: vec![0; lines.len()],
+            lines,
+            active_low,
+            edge: info.edge().unwrap_or(EdgeDetect::Disable),
+            events: VecDeque::new(),
+            seqno: 0,
+        });
+
+        Ok(MockLines {
+            dir: PhantomData,
+            info,
+            shared: self.shared.clone(),
+            id,
+        })
+    }
+
+    /// Get physical level of line
+    pub fn level(&self, line: LineId) -> Result<bool> {
+        self.shared
+            .lock()
+            .levels
+            .as_slice()
+            .get(line as usize)
+            .copied()
+            .ok_or_else(|| Error::LineNotFound(line.to_string()))
+    }
+
+    /// Set physical level of line as if it is driven externally
+    ///
+    /// When level changes the edge events are queued to requests which detects it.
+    pub fn set_level(&self, line: LineId, level: bool) -> Result<()> {
+        let mut state = self.shared.lock();
+
+        let current = state
+            .levels
+            .get_mut(line as usize)
+            .ok_or_else(|| Error::LineNotFound(line.to_string()))?;
+
+        if *current == level {
+            return Ok(());
+        }
+
+        *current = level;
+
+        let time = self.shared.start.elapsed();
+
+        for request in &mut state.requests {
+            let id = match request.lines.iter().position(|&other| other == line) {
+                Some(id) => id,
+                None => continue,
+            };
+
+            let edge = if level != request.active_low {
+                Edge::Rising
+            } else {
+                Edge::Falling
+            };
+
+            let detect = match request.edge {
+                EdgeDetect::Disable => false,
+                EdgeDetect::Rising => edge == Edge::Rising,
+                EdgeDetect::Falling => edge == Edge::Falling,
+                EdgeDetect::Both => true,
+            };
+
+            if !detect {
+                continue;
+            }
+
+            request.seqno += 1;
+            request.line_seqno[id] += 1;
+
+            let v2 = cfg!(feature = "v2");
+
+            request.events.push_back(Event {
+                line: id as _,
+                offset: line,
+                edge,
+                time,
+                clock: Default::default(),
+                seqno: v2.then_some(request.seqno),
+                line_seqno: v2.then_some(request.line_seqno[id]),
+            });
+        }
+
+        self.shared.ready.notify_all();
+
+        Ok(())
+    }
+}
+
+/// Simulated GPIO lines
+///
+/// Use [MockChip::request_lines] to create it.
+pub struct MockLines<Direction> {
+    dir: PhantomData<Direction>,
+    info: Internal<ValuesInfo>,
+    shared: Arc<MockShared>,
+    id: u64,
+}
+
+impl<Direction> Deref for MockLines<Direction> {
+    type Target = ValuesInfo;
+
+    fn deref(&self) -> &Self::Target {
+        &self.info
+    }
+}
+
+impl<Direction> Drop for MockLines<Direction> {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+
+        for &line in self.info.lines() {
+            state.requested[line as usize] = false;
+        }
+
+        state.requests.retain(|request| request.id != self.id);
+    }
+}
+
+impl<Direction> MockLines<Direction> {
+    fn active_low(&self) -> bool {
+        matches!(self.info.active(), crate::Active::Low)
+    }
+
+    /// Get the value of GPIO lines
+    ///
+    /// Same as [crate::Lines::get_values].
+    pub fn get_values<T: AsValuesMut>(&self, mut values: T) -> Result<T> {
+        let active_low = self.active_low();
+        let state = self.shared.lock();
+
+        for (id, &line) in self.info.lines().iter().enumerate() {
+            if values.get(id as _).is_some() {
+                values.set(id as _, Some(state.levels[line as usize] != active_low));
+            }
+        }
+
+        for id in self.info.lines().len() as BitId..values.bits() {
+            values.set(id, Some(false));
+            values.set(id, None);
+        }
+
+        Ok(values)
+    }
+}
+
+impl MockLines<Input> {
+    fn pop_event(&self, state: &mut MockState) -> Option<Event> {
+        state
+            .requests
+            .iter_mut()
+            .find(|request| request.id == self.id)
+            .and_then(|request| request.events.pop_front())
+    }
+
+    /// Read single GPIO event
+    ///
+    /// Blocks until event is injected using [MockChip::set_level]. Like [crate::Lines::read_event]
+    /// it blocks forever when edge detection is disabled.
+    pub fn read_event(&mut self) -> Result<Event> {
+        let mut state = self.shared.lock();
+
+        loop {
+            if let Some(event) = self.pop_event(&mut state) {
+                return Ok(event);
+            }
+
+            state = self
+                .shared
+                .ready
+                .wait(state)
+                .unwrap_or_else(|error| error.into_inner());
+        }
+    }
+
+    /// Read single GPIO event when available
+    ///
+    /// Returns `None` without blocking when no events pending.
+    pub fn try_read_event(&mut self) -> Result<Option<Event>> {
+        Ok(self.pop_event(&mut self.shared.lock()))
+    }
+}
+
+impl MockLines<Output> {
+    /// Set the value of GPIO lines
+    ///
+    /// Same as [crate::Lines::set_values].
+    pub fn set_values<T: AsValues>(&self, values: T) -> Result<()> {
+        let active_low = self.active_low();
+        let mut state = self.shared.lock();
+
+        for (id, &line) in self.info.lines().iter().enumerate() {
+            if let Some(value) = values.get(id as _) {
+                state.levels[line as usize] = value != active_low;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<Direction: DirectionType> GpioLines for MockLines<Direction> {
+    type Direction = Direction;
+
+    fn get_values<T: AsValuesMut>(&self, values: T) -> Result<T> {
+        MockLines::get_values(self, values)
+    }
+}
+
+impl GpioInputs for MockLines<Input> {
+    fn read_event(&mut self) -> Result<Event> {
+        MockLines::read_event(self)
+    }
+
+    fn try_read_event(&mut self) -> Result<Option<Event>> {
+        MockLines::try_read_event(self)
+    }
+}
+
+impl GpioOutputs for MockLines<Output> {
+    fn set_values<T: AsValues>(&self, values: T) -> Result<()> {
+        MockLines::set_values(self, values)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Active;
+
+    #[test]
+    fn mock_toggle_output() {
+        let chip = MockChip::new("gpiochip0", "gpio-mock", 4);
+
+        let output = chip
+            .request_lines(Options::output([1, 3]).values([false, true]))
+            .unwrap();
+
+        assert!(!chip.level(1).unwrap());
+        assert!(chip.level(3).unwrap());
+
+        for _ in 0..2 {
+            let values = output.get_values([false; 2]).unwrap();
+            output.set_values([!values[0], !values[1]]).unwrap();
+        }
+
+        assert_eq!(output.get_values([false; 2]).unwrap(), [false, true]);
+
+        output.set_values([Some(true), None]).unwrap();
+        assert_eq!(output.get_values(0u8).unwrap(), 0b11);
+    }
+
+    #[test]
+    fn mock_initial_values() {
+        let chip = MockChip::new("gpiochip0", "gpio-mock", 8);
+
+        let output = chip
+            .request_lines(Options::output([0, 1, 2]).values(0xffu8))
+            .unwrap();
+
+        // only requested lines are driven
+        for line in 0..8 {
+            assert_eq!(chip.level(line).unwrap(), line < 3);
+        }
+        assert_eq!(output.get_values(0u8).unwrap(), 0b111);
+
+        let error = chip
+            .request_lines(Options::output([4, 5]).values([true]))
+            .err()
+            .unwrap();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn mock_chips_order() {
+        let mut chips = ["gpiochip2", "gpio-sim", "gpiochip10", "gpiochip0"]
+            .map(|name| MockChip::new(name, "gpio-mock", 4));
+
+        // same order as Chip::all
+        crate::sort_chips(&mut chips);
+
+        assert_eq!(
+            chips.iter().map(|chip| chip.name()).collect::<Vec<_>>(),
+            ["gpiochip10", "gpiochip2", "gpiochip0", "gpio-sim"]
+        );
+        assert_eq!(chips[0].num_lines(), 4);
+        assert_eq!(chips[0].label(), "gpio-mock");
+    }
+
+    #[test]
+    fn mock_busy_lines() {
+        let chip = MockChip::new("gpiochip0", "gpio-mock", 4);
+
+        let output = chip.request_lines(Options::output([1])).unwrap();
+
+        let error = chip.request_lines(Options::input([0, 1])).err().unwrap();
+        assert!(matches!(error, Error::LineBusy(_)));
+
+        drop(output);
+
+        assert!(chip.request_lines(Options::input([0, 1])).is_ok());
+
+        let error = chip.request_lines(Options::input([4])).err().unwrap();
+        assert!(matches!(error, Error::LineNotFound(_)));
+    }
+
+    #[test]
+    fn mock_input_events() {
+        let chip = MockChip::new("gpiochip0", "gpio-mock", 8);
+
+        let mut input = chip
+            .request_lines(
+                Options::input([6, 2])
+                    .active(Active::Low)
+                    .edge(EdgeDetect::Falling),
+            )
+            .unwrap();
+
+        assert_eq!(input.get_values([false; 2]).unwrap(), [true, true]);
+
+        chip.set_level(2, true).unwrap();
+        chip.set_level(2, true).unwrap();
+        chip.set_level(6, false).unwrap();
+        chip.set_level(2, false).unwrap();
+
+        assert_eq!(input.get_values([false; 2]).unwrap(), [true, true]);
+
+        let event = input.try_read_event().unwrap().unwrap();
+        assert_eq!(event.line, 1);
+        assert_eq!(event.offset, 2);
+        assert_eq!(event.edge, Edge::Falling);
+        assert!(input.try_read_event().unwrap().is_none());
+
+        let waiter = std::thread::spawn(move || input.read_event().unwrap());
+        chip.set_level(6, true).unwrap();
+
+        let event = waiter.join().unwrap();
+        assert_eq!(event.offset, 6);
+        assert_eq!(event.edge, Edge::Falling);
+    }
+
+    // logic written against lines interface
+    fn follow(input: &mut impl GpioInputs, output: &impl GpioOutputs) -> Result<()> {
+        let event = input.read_event()?;
+
+        output.set_values([event.edge == Edge::Rising])
+    }
+
+    #[test]
+    fn mock_generic_lines() {
+        let chip = MockChip::new("gpiochip0", "gpio-mock", 4);
+
+        let mut input = chip
+            .request_lines(Options::input([0]).edge(EdgeDetect::Both))
+            .unwrap();
+        let output = chip.request_lines(Options::output([3])).unwrap();
+
+        chip.set_level(0, true).unwrap();
+        follow(&mut input, &output).unwrap();
+        assert!(chip.level(3).unwrap());
+
+        chip.set_level(0, false).unwrap();
+        follow(&mut input, &output).unwrap();
+        assert!(!chip.level(3).unwrap());
+
+        assert!(GpioInputs::try_read_event(&mut input).unwrap().is_none());
+    }
+
+    #[test]
+    fn mock_read_event_without_edge() {
+        let chip = MockChip::new("gpiochip0", "gpio-mock", 4);
+
+        let mut input = chip.request_lines(Options::input([1])).unwrap();
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || sender.send(input.read_event().is_ok()));
+
+        chip.set_level(1, true).unwrap();
+
+        // blocks like real lines instead of failing
+        let result = receiver.recv_timeout(std::time::Duration::from_millis(50));
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,198 @@
+use crate::{invalid_input, Edge, EdgeDetect, Input, Lines, Result};
+use embedded_hal::digital;
+use embedded_hal_async::digital::Wait;
+use std::fmt;
+
+impl Lines<Input> {
+    fn check_wait(&self, edge: Option<Edge>) -> Result<()> {
+        if self.lines().len() != 1 {
+            return Err(invalid_input("Single line expected"));
+        }
+
+        let detect = self.edge().unwrap_or(EdgeDetect::Disable);
+
+        let detected = match (detect, edge) {
+            (EdgeDetect::Disable, _) => false,
+            (EdgeDetect::Both, _) | (_, None) => true,
+            (EdgeDetect::Rising, Some(edge)) => edge == Edge::Rising,
+            (EdgeDetect::Falling, Some(edge)) => edge == Edge::Falling,
+        };
+
+        if !detected {
+            return Err(invalid_input("Edge detection does not match"));
+        }
+
+        Ok(())
+    }
+
+    async fn next_edge(&mut self, edge: Option<Edge>) -> Result<()> {
+        loop {
+            let event = self.read_event().await?;
+
+            if edge.map(|edge| edge == event.edge).unwrap_or(true) {
+                return Ok(());
+            }
+        }
+    }
+
+    async fn wait_level(&mut self, level: bool) -> Result<()> {
+        let edge = if level { Edge::Rising } else { Edge::Falling };
+
+        self.check_wait(Some(edge))?;
+
+        // queued edges may be outdated so the level is checked after each one
+        while self.get_values([false]).await?[0] != level {
+            self.next_edge(Some(edge)).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn wait_for_edge(&mut self, edge: Option<Edge>) -> Result<()> {
+        self.check_wait(edge)?;
+        self.next_edge(edge).await
+    }
+}
+
+impl digital::ErrorType for Lines<Input> {
+    type Error = PinError;
+}
+
+/// Waiting for single input line using edge events
+///
+/// The lines should be requested with single line only and edge detection which covers
+/// the awaited edges. Edges which occurred since the previous wait are not lost, so the
+/// edge waits can complete immediately using the queued events.
+impl Wait for Lines<Input> {
+    async fn wait_for_high(&mut self) -> core::result::Result<(), PinError> {
+        Ok(self.wait_level(true).await?)
+    }
+
+    async fn wait_for_low(&mut self) -> core::result::Result<(), PinError> {
+        Ok(self.wait_level(false).await?)
+    }
+
+    async fn wait_for_rising_edge(&mut self) -> core::result::Result<(), PinError> {
+        Ok(self.wait_for_edge(Some(Edge::Rising)).await?)
+    }
+
+    async fn wait_for_falling_edge(&mut self) -> core::result::Result<(), PinError> {
+        Ok(self.wait_for_edge(Some(Edge::Falling)).await?)
+    }
+
+    async fn wait_for_any_edge(&mut self) -> core::result::Result<(), PinError> {
+        Ok(self.wait_for_edge(None).await?)
+    }
+}
+
+/// Error of [Wait] operations
+#[derive(Debug)]
+pub struct PinError(crate::Error);
+
+impl PinError {
+    /// Get underlying GPIO error
+    pub fn into_inner(self) -> crate::Error {
+        self.0
+    }
+}
+
+impl From<crate::Error> for PinError {
+    fn from(error: crate::Error) -> Self {
+        Self(error)
+    }
+}
+
+impl fmt::Display for PinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for PinError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+impl digital::Error for PinError {
+    fn kind(&self) -> digital::ErrorKind {
+        digital::ErrorKind::Other
+    }
+}
+
+#[cfg(all(test, feature = "v2"))]
+mod test {
+    use super::*;
+    use crate::{File, Internal, Options, ValuesInfo};
+    use futures_util::FutureExt;
+    use gpiod_core::RawEvent;
+    use std::{
+        io::Write,
+        marker::PhantomData,
+        os::unix::{io::IntoRawFd, net::UnixStream},
+        sync::Arc,
+        time::Duration,
+    };
+    use tokio::time::{sleep, timeout};
+
+    // Event ids as defined by kernel ABI v2
+    const RISING_EDGE: u32 = 1;
+    const FALLING_EDGE: u32 = 2;
+
+    fn mock_lines(edge: EdgeDetect) -> (Lines<Input>, UnixStream) {
+        let (reader, writer) = UnixStream::pair().unwrap();
+        // lines fd is expected to be non-blocking
+        reader.set_nonblocking(true).unwrap();
+        let options = Options::input([5]).edge(edge);
+
+        let lines = Lines {
+            dir: PhantomData,
+            info: Arc::new(Internal::<ValuesInfo>::new("gpiochip0", &options).unwrap()),
+            file: File::from_fd(reader.into_raw_fd()).unwrap(),
+            events: Default::default(),
+        };
+
+        (lines, writer)
+    }
+
+    fn write_event(writer: &mut UnixStream, id: u32) {
+        let mut event = RawEvent::default();
+        event.offset = 5;
+        event.id = id;
+        writer.write_all(event.as_mut()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn wait_rising_edge() {
+        let (mut lines, mut writer) = mock_lines(EdgeDetect::Both);
+
+        let inject = async {
+            sleep(Duration::from_millis(20)).await;
+            write_event(&mut writer, FALLING_EDGE);
+            write_event(&mut writer, RISING_EDGE);
+        };
+
+        let (result, _) = timeout(Duration::from_secs(5), async {
+            tokio::join!(lines.wait_for_rising_edge(), inject)
+        })
+        .await
+        .unwrap();
+
+        result.unwrap();
+        // all injected events should be consumed
+        assert!(lines.read_event().now_or_never().is_none());
+    }
+
+    #[tokio::test]
+    async fn wait_edge_mismatch() {
+        let (mut lines, _writer) = mock_lines(EdgeDetect::Falling);
+
+        let error = lines.wait_for_rising_edge().await.unwrap_err();
+        assert_eq!(error.into_inner().kind(), std::io::ErrorKind::InvalidInput);
+
+        let (mut lines, _writer) = mock_lines(EdgeDetect::Disable);
+
+        assert!(lines.wait_for_any_edge().await.is_err());
+        assert!(lines.wait_for_high().await.is_err());
+    }
+}